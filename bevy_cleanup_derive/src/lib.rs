@@ -4,20 +4,119 @@
 //! Derive macros for `bevy_cleanup`.
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, Path};
 
 /// Automatically implements the [`bevy_cleanup::Cleanup`] trait for a type. You must also derive [`Component`].
 /// 
-/// This will simply make an empty impl block for the type, since Cleanup is just a marker trait.
-#[proc_macro_derive(Cleanup)]
+/// For unit structs, `Cleanup::marker` is implemented, so that the type can be used with
+/// `SpawnScopedExt::spawn_scoped`.
+///
+/// # Attributes
+///
+/// - `#[cleanup(state = AppState::Game)]` registers this type to be cleaned up when exiting the
+///   given state variant, once `add_cleanups::<AppState>()` is called on the app. This attribute
+///   may be repeated to register the type for multiple variants, in which case
+///   `allow_multiple_cleanup_variants` is also called for the type.
+#[proc_macro_derive(Cleanup, attributes(cleanup))]
 pub fn derive_cleanup(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let generics = &input.generics;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let variants = match state_variants(&input) {
+        Ok(variants) => variants,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    if !variants.is_empty() && !generics.params.is_empty() {
+        return syn::Error::new(
+            generics.span(),
+            "`#[cleanup(state = ...)]` cannot be used on generic types",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // the type is intentionally cleaned up when exiting multiple variants
+    let allow_multiple = (variants.len() > 1).then(|| {
+        quote! {
+            bevy_cleanup::AddStateCleanup::allow_multiple_cleanup_variants::<#name>(app);
+        }
+    });
+
+    let registrations = variants.iter().map(|variant| {
+        let mut state = variant.clone();
+        state.segments.pop();
+        state.segments.pop_punct();
+
+        quote_spanned! { variant.span() =>
+            bevy_cleanup::__private::inventory::submit! {
+                bevy_cleanup::CleanupRegistration::new::<#state>(|app| {
+                    #allow_multiple
+                    bevy_cleanup::AddStateCleanup::add_state_cleanup::<#state, #name>(
+                        app,
+                        #variant,
+                    );
+                })
+            }
+        }
+    });
+
+    let marker = match &input.data {
+        Data::Struct(data) if matches!(data.fields, Fields::Unit) => quote! {
+            fn marker() -> Option<Self> {
+                Some(Self)
+            }
+        },
+        _ => quote! {},
+    };
+
+    TokenStream::from(quote! {
+        impl #impl_generics bevy_cleanup::Cleanup for #name #type_generics #where_clause {
+            #marker
+        }
+
+        #(#registrations)*
+    })
+}
+
+fn state_variants(input: &DeriveInput) -> syn::Result<Vec<Path>> {
+    let mut variants = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("cleanup")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("state") {
+                let variant: Path = meta.value()?.parse()?;
+                if variant.segments.len() < 2 {
+                    return Err(syn::Error::new(
+                        variant.span(),
+                        "expected a path to a state variant, like `AppState::Game`",
+                    ));
+                }
+                variants.push(variant);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported cleanup attribute"))
+            }
+        })?;
+    }
+    Ok(variants)
+}
+
+/// Automatically implements the [`bevy_cleanup::CleanupResource`] trait for a type. You must also
+/// derive [`Resource`].
+///
+/// This will simply make an empty impl block for the type, since CleanupResource is just a marker
+/// trait.
+#[proc_macro_derive(CleanupResource)]
+pub fn derive_cleanup_resource(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
     let generics = input.generics;
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
     TokenStream::from(quote! {
-        impl #impl_generics bevy_cleanup::Cleanup for #name #type_generics #where_clause {}
+        impl #impl_generics bevy_cleanup::CleanupResource for #name #type_generics #where_clause {}
     })
 }
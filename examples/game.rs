@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use bevy::{app::ScheduleRunnerPlugin, prelude::*};
-use bevy_cleanup::{AddStateCleanup, Cleanup};
+use bevy_cleanup::{AddStateCleanup, Cleanup, SpawnScopedExt};
 
 fn main() {
     // Boring setup stuff
@@ -101,10 +101,10 @@ pub struct Health(pub f32);
 pub struct DamageOverTime(pub f32);
 
 fn setup_game(mut commands: Commands) {
-    commands.spawn((
+    // Instead of adding `CleanupGame` ourselves, we can let `spawn_scoped` add the cleanup type
+    // registered for the current state. Since we're in `AppState::Game`, this adds `CleanupGame`.
+    commands.spawn_scoped::<AppState>((
         Name::new("Player"),
-        CleanupGame,
-        //
         Health(1.0),
         // 0.25 damage per sec; 4 seconds to kill the player
         DamageOverTime(0.25),
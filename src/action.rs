@@ -0,0 +1,304 @@
+use bevy::{
+    ecs::{system::Command, world::EntityMut},
+    hierarchy::despawn_with_children_recursive,
+    prelude::*,
+    transform::commands::{AddChildInPlace, RemoveParentInPlace},
+};
+
+use crate::{hook, Cleanup};
+
+/// What happens to an entity with a [`Cleanup`] component when its cleanup runs.
+///
+/// By default, entities are recursively despawned, but you can pick a different action per
+/// registration, e.g. using [`AddStateCleanup::add_state_cleanup_with`], if you want some
+/// entities to be disabled rather than destroyed.
+///
+/// [`AddStateCleanup::add_state_cleanup_with`]: crate::AddStateCleanup::add_state_cleanup_with
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum CleanupAction {
+    /// Despawns the entity and all of its descendants.
+    ///
+    /// Descendants with a [`KeepOnCleanup`] component are detached from the hierarchy before
+    /// despawning, and are kept alive along with their own descendants.
+    #[default]
+    DespawnRecursive,
+    /// Despawns only the entity itself. Its children are reparented to the entity's own parent,
+    /// or become root entities if it has no parent, keeping their [`GlobalTransform`] so that they
+    /// stay in place.
+    Despawn,
+    /// Removes the cleanup marker component from the entity, leaving the rest of it untouched.
+    RemoveMarker,
+    /// Removes a bundle from the entity, using the given function.
+    ///
+    /// Use [`CleanupAction::remove`] to create this for a specific bundle type.
+    RemoveBundle(fn(&mut EntityMut)),
+    /// Hides the entity by setting its [`Visibility`] to [`Visibility::Hidden`].
+    #[cfg(feature = "render")]
+    Hide,
+}
+
+impl CleanupAction {
+    /// Creates an action which removes the bundle `B` from the entity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::CleanupAction;
+    ///
+    /// #[derive(Component)]
+    /// struct Health(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Controllable;
+    ///
+    /// let action = CleanupAction::remove::<(Health, Controllable)>();
+    /// ```
+    pub fn remove<B: Bundle>() -> Self {
+        Self::RemoveBundle(|entity| {
+            entity.remove::<B>();
+        })
+    }
+
+    /// Applies this action to `entity`, which has the cleanup marker `C`.
+    ///
+    /// Before the action is applied, the [`CleanupHook`]s of the affected entities are run.
+    ///
+    /// If the entity no longer exists, e.g. because it was a descendant of an entity which was
+    /// already recursively despawned, this does nothing.
+    ///
+    /// [`CleanupHook`]: crate::CleanupHook
+    pub fn apply<C: Cleanup>(&self, world: &mut World, entity: Entity) {
+        if world.get_entity(entity).is_none() {
+            return;
+        }
+
+        hook::run(world, entity);
+        if let Self::DespawnRecursive = self {
+            let (descendants, kept) = descendants(world, entity);
+            for descendant in descendants {
+                hook::run(world, descendant);
+            }
+            for child in kept {
+                RemoveParentInPlace { child }.apply(world);
+            }
+        }
+
+        // the hooks may have despawned the entity themselves
+        let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+            return;
+        };
+
+        match self {
+            Self::DespawnRecursive => despawn_with_children_recursive(world, entity),
+            Self::Despawn => {
+                let parent = entity_mut.get::<Parent>().map(|parent| parent.get());
+                if let Some(children) = entity_mut.take::<Children>() {
+                    for &child in &children {
+                        // `Children` may still contain entities which were despawned directly
+                        if world.get_entity(child).is_none() {
+                            continue;
+                        }
+                        match parent {
+                            Some(parent) => AddChildInPlace { parent, child }.apply(world),
+                            None => RemoveParentInPlace { child }.apply(world),
+                        }
+                    }
+                }
+                despawn_with_children_recursive(world, entity);
+            }
+            Self::RemoveMarker => {
+                entity_mut.remove::<C>();
+            }
+            Self::RemoveBundle(remove) => remove(&mut entity_mut),
+            #[cfg(feature = "render")]
+            Self::Hide => {
+                entity_mut.insert(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+/// A component which keeps an entity alive when one of its ancestors is recursively despawned by
+/// a cleanup.
+///
+/// Before the ancestor is despawned, this entity is detached from the hierarchy, and its
+/// [`Transform`] is set to its current [`GlobalTransform`] so that it stays in place. Its own
+/// descendants are kept along with it.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, KeepOnCleanup};
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// fn setup_level(mut commands: Commands) {
+///     commands
+///         .spawn((Name::new("Level"), CleanupGame, TransformBundle::default()))
+///         .with_children(|parent| {
+///             // the camera will survive the level being despawned
+///             parent.spawn((Name::new("Camera"), KeepOnCleanup, TransformBundle::default()));
+///         });
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct KeepOnCleanup;
+
+/// Collects all descendants of `entity` which will be despawned along with it, depth-first, in
+/// [`Children`] order, and all descendants which are [`KeepOnCleanup`].
+fn descendants(world: &World, entity: Entity) -> (Vec<Entity>, Vec<Entity>) {
+    let mut descendants = Vec::new();
+    let mut kept = Vec::new();
+    let mut stack = world
+        .get::<Children>(entity)
+        .map(|children| children.iter().rev().copied().collect::<Vec<_>>())
+        .unwrap_or_default();
+    while let Some(entity) = stack.pop() {
+        if world.get::<KeepOnCleanup>(entity).is_some() {
+            kept.push(entity);
+            continue;
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().rev());
+        }
+        descendants.push(entity);
+    }
+    (descendants, kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{CleanupAction, KeepOnCleanup};
+    use crate as bevy_cleanup;
+    use crate::Cleanup;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupTest;
+
+    #[derive(Component)]
+    struct Health;
+
+    fn family(world: &mut World) -> (Entity, Entity, Entity) {
+        let grandparent = world.spawn_empty().id();
+        let parent = world.spawn(CleanupTest).set_parent(grandparent).id();
+        let child = world.spawn_empty().set_parent(parent).id();
+        (grandparent, parent, child)
+    }
+
+    #[test]
+    fn despawn_recursive() {
+        let mut world = World::new();
+        let (grandparent, parent, child) = family(&mut world);
+
+        CleanupAction::DespawnRecursive.apply::<CleanupTest>(&mut world, parent);
+        assert!(world.get_entity(grandparent).is_some());
+        assert!(world.get_entity(parent).is_none());
+        assert!(world.get_entity(child).is_none());
+        assert!(world.get::<Children>(grandparent).unwrap().is_empty());
+    }
+
+    #[test]
+    fn despawn_recursive_keeps_marked() {
+        let mut world = World::new();
+        let (_, parent, child) = family(&mut world);
+        world.entity_mut(parent).insert(TransformBundle {
+            local: Transform::from_xyz(1.0, 0.0, 0.0),
+            global: GlobalTransform::from_xyz(1.0, 0.0, 0.0),
+        });
+        world.entity_mut(child).insert((
+            KeepOnCleanup,
+            TransformBundle {
+                local: Transform::from_xyz(0.0, 2.0, 0.0),
+                global: GlobalTransform::from_xyz(1.0, 2.0, 0.0),
+            },
+        ));
+        let grandchild = world.spawn_empty().set_parent(child).id();
+
+        CleanupAction::DespawnRecursive.apply::<CleanupTest>(&mut world, parent);
+        assert!(world.get_entity(parent).is_none());
+        assert!(world.get::<Parent>(child).is_none());
+        assert_eq!(
+            Transform::from_xyz(1.0, 2.0, 0.0),
+            *world.get::<Transform>(child).unwrap()
+        );
+        assert_eq!(child, world.get::<Parent>(grandchild).unwrap().get());
+    }
+
+    #[test]
+    fn despawn_reparents_children() {
+        let mut world = World::new();
+        let (grandparent, parent, child) = family(&mut world);
+
+        CleanupAction::Despawn.apply::<CleanupTest>(&mut world, parent);
+        assert!(world.get_entity(parent).is_none());
+        assert_eq!(grandparent, world.get::<Parent>(child).unwrap().get());
+        assert_eq!(&[child], &**world.get::<Children>(grandparent).unwrap());
+
+        let root = world.spawn(CleanupTest).id();
+        let child = world.spawn_empty().set_parent(root).id();
+        CleanupAction::Despawn.apply::<CleanupTest>(&mut world, root);
+        assert!(world.get_entity(root).is_none());
+        assert!(world.get::<Parent>(child).is_none());
+    }
+
+    #[test]
+    fn despawn_keeps_children_in_place() {
+        let mut world = World::new();
+        let (grandparent, parent, child) = family(&mut world);
+        world.entity_mut(grandparent).insert(TransformBundle::default());
+        world.entity_mut(parent).insert(TransformBundle {
+            local: Transform::from_xyz(1.0, 0.0, 0.0),
+            global: GlobalTransform::from_xyz(1.0, 0.0, 0.0),
+        });
+        world.entity_mut(child).insert(TransformBundle {
+            local: Transform::from_xyz(0.0, 2.0, 0.0),
+            global: GlobalTransform::from_xyz(1.0, 2.0, 0.0),
+        });
+
+        CleanupAction::Despawn.apply::<CleanupTest>(&mut world, parent);
+        assert_eq!(
+            Transform::from_xyz(1.0, 2.0, 0.0),
+            *world.get::<Transform>(child).unwrap()
+        );
+    }
+
+    #[test]
+    fn despawn_with_stale_child() {
+        let mut world = World::new();
+        let (grandparent, parent, child) = family(&mut world);
+        let stale = world.spawn_empty().set_parent(parent).id();
+        world.despawn(stale);
+
+        CleanupAction::Despawn.apply::<CleanupTest>(&mut world, parent);
+        assert!(world.get_entity(parent).is_none());
+        assert_eq!(grandparent, world.get::<Parent>(child).unwrap().get());
+    }
+
+    #[test]
+    fn remove_marker_and_bundle() {
+        let mut world = World::new();
+        let entity = world.spawn((CleanupTest, Health)).id();
+
+        CleanupAction::RemoveMarker.apply::<CleanupTest>(&mut world, entity);
+        assert!(!world.entity(entity).contains::<CleanupTest>());
+        assert!(world.entity(entity).contains::<Health>());
+
+        CleanupAction::remove::<Health>().apply::<CleanupTest>(&mut world, entity);
+        assert!(!world.entity(entity).contains::<Health>());
+    }
+
+    #[test]
+    fn missing_entity() {
+        let mut world = World::new();
+        let entity = world.spawn(CleanupTest).id();
+        world.despawn(entity);
+
+        CleanupAction::RemoveMarker.apply::<CleanupTest>(&mut world, entity);
+    }
+}
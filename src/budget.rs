@@ -0,0 +1,289 @@
+use std::{any::type_name, collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, utils::Instant};
+
+use crate::{mode::dry_run, stats::record_stats, Cleanup, CleanupAction, CleanupReport};
+
+/// How much work a budgeted cleanup may do in a single frame.
+///
+/// The budget covers all work done in the frame, including the work of other budgeted cleanups
+/// which ran before it in the same frame. At least one entity is always cleaned up per frame, so
+/// that the cleanup finishes even with a budget of zero.
+///
+/// See [`AddStateCleanup::add_state_cleanup_budgeted`].
+///
+/// [`AddStateCleanup::add_state_cleanup_budgeted`]:
+///     crate::AddStateCleanup::add_state_cleanup_budgeted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CleanupBudget {
+    /// Cleans up at most this many entities per frame.
+    Entities(usize),
+    /// Keeps cleaning up entities until this much time has been spent in the frame.
+    Time(Duration),
+}
+
+/// A component which marks an entity as waiting to be cleaned up by a budgeted cleanup.
+///
+/// Entities are given this when the cleanup starts, so systems which should ignore them in the
+/// meantime can filter them out using `Without<CleanupPending>`. If the cleanup despawns entities
+/// and the `render` feature is enabled, entities with a `Visibility` are also hidden.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct CleanupPending;
+
+/// The budgeted cleanups which have started, but haven't cleaned up all of their entities yet.
+///
+/// This resource is added by [`AddStateCleanup::add_state_cleanup_budgeted`], and can be used with
+/// the [`cleanup_in_progress`] run condition, e.g. to keep showing a loading screen until all
+/// entities of the previous state are gone.
+///
+/// [`AddStateCleanup::add_state_cleanup_budgeted`]:
+///     crate::AddStateCleanup::add_state_cleanup_budgeted
+#[derive(Default, Resource)]
+pub struct CleanupInProgress {
+    batches: VecDeque<Batch>,
+}
+
+impl CleanupInProgress {
+    /// Returns `true` if all budgeted cleanups have finished.
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// Returns the number of entities which are still waiting to be cleaned up.
+    pub fn remaining(&self) -> usize {
+        self.batches.iter().map(|batch| batch.remaining.len()).sum()
+    }
+}
+
+/// A run condition which is `true` while a budgeted cleanup is still in progress.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::cleanup_in_progress;
+///
+/// fn show_loading_screen() {}
+///
+/// App::new().add_systems(Update, show_loading_screen.run_if(cleanup_in_progress));
+/// ```
+pub fn cleanup_in_progress(progress: Option<Res<CleanupInProgress>>) -> bool {
+    progress.is_some_and(|progress| !progress.is_empty())
+}
+
+struct Batch {
+    state: Option<&'static str>,
+    schedule: Option<String>,
+    marker: &'static str,
+    action: CleanupAction,
+    budget: CleanupBudget,
+    apply: fn(&CleanupAction, &mut World, Entity),
+    remaining: VecDeque<Entity>,
+    cleaned: Vec<Entity>,
+    duration: Duration,
+}
+
+/// Starts a budgeted cleanup of all entities with `C` which aren't already pending.
+pub(crate) fn start_budgeted_cleanup<C: Cleanup>(
+    state: Option<&'static str>,
+    schedule: String,
+    action: CleanupAction,
+    budget: CleanupBudget,
+) -> impl FnMut(&mut World) {
+    move |world: &mut World| {
+        let mut entities = world
+            .query_filtered::<Entity, (With<C>, Without<CleanupPending>)>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        entities.sort();
+        let marker = type_name::<C>();
+        if dry_run(world, &entities, marker, state, Some(&schedule)) {
+            return;
+        }
+
+        for &entity in &entities {
+            let mut entity_mut = world.entity_mut(entity);
+            entity_mut.insert(CleanupPending);
+            #[cfg(feature = "render")]
+            if matches!(
+                action,
+                CleanupAction::DespawnRecursive | CleanupAction::Despawn
+            ) && entity_mut.contains::<Visibility>()
+            {
+                entity_mut.insert(Visibility::Hidden);
+            }
+        }
+
+        world
+            .resource_mut::<CleanupInProgress>()
+            .batches
+            .push_back(Batch {
+                state,
+                schedule: Some(schedule.clone()),
+                marker,
+                action,
+                budget,
+                apply: |action, world, entity| action.apply::<C>(world, entity),
+                remaining: entities.into(),
+                cleaned: Vec::new(),
+                duration: Duration::ZERO,
+            });
+    }
+}
+
+/// Cleans up the entities of pending budgeted cleanups, until a cleanup runs out of budget for the
+/// frame.
+pub(crate) fn run_budgeted_cleanups(world: &mut World) {
+    let start = Instant::now();
+    let mut progress = std::mem::take(&mut *world.resource_mut::<CleanupInProgress>());
+
+    // the number of entities cleaned up this frame, over all batches
+    let mut cleaned = 0;
+    while let Some(batch) = progress.batches.front_mut() {
+        let batch_start = Instant::now();
+        while let Some(&entity) = batch.remaining.front() {
+            let exhausted = cleaned > 0
+                && match batch.budget {
+                    CleanupBudget::Entities(max) => cleaned >= max,
+                    CleanupBudget::Time(max) => start.elapsed() >= max,
+                };
+            if exhausted {
+                break;
+            }
+
+            batch.remaining.pop_front();
+            (batch.apply)(&batch.action, world, entity);
+            if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                entity_mut.remove::<CleanupPending>();
+            }
+            batch.cleaned.push(entity);
+            cleaned += 1;
+        }
+        batch.duration += batch_start.elapsed();
+
+        if !batch.remaining.is_empty() {
+            break;
+        }
+        let batch = progress.batches.pop_front().expect("batch should exist");
+        record_stats(world, batch.marker, batch.cleaned.len(), batch.duration);
+        if let Some(mut reports) = world.get_resource_mut::<Events<CleanupReport>>() {
+            reports.send(CleanupReport {
+                state: batch.state,
+                schedule: batch.schedule,
+                marker: batch.marker,
+                entities: batch.cleaned,
+            });
+        }
+    }
+
+    *world.resource_mut::<CleanupInProgress>() = progress;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{cleanup_in_progress, CleanupBudget, CleanupInProgress, CleanupPending};
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+        Menu,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupLevel;
+
+    #[derive(Default, Resource)]
+    struct LoadingFrames(usize);
+
+    #[test]
+    fn cleanup_over_frames() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup_budgeted::<_, CleanupGame>(
+                AppState::Game,
+                CleanupBudget::Entities(2),
+            )
+            .init_resource::<LoadingFrames>()
+            .add_systems(
+                Update,
+                (|mut frames: ResMut<LoadingFrames>| frames.0 += 1).run_if(cleanup_in_progress),
+            );
+        for _ in 0..5 {
+            app.world.spawn(CleanupGame);
+        }
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(3, app.world.entities().len());
+        assert_eq!(
+            3,
+            app.world
+                .query_filtered::<(), With<CleanupPending>>()
+                .iter(&app.world)
+                .count()
+        );
+
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+        assert!(app.world.resource::<CleanupInProgress>().is_empty());
+        assert_eq!(3, app.world.resource::<LoadingFrames>().0);
+    }
+
+    #[test]
+    fn zero_budget_finishes() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup_budgeted::<_, CleanupGame>(
+                AppState::Game,
+                CleanupBudget::Entities(0),
+            );
+        app.world.spawn(CleanupGame);
+        app.world.spawn(CleanupGame);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+        assert!(app.world.resource::<CleanupInProgress>().is_empty());
+    }
+
+    #[test]
+    fn budget_shared_between_batches() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup_budgeted::<_, CleanupGame>(
+                AppState::Game,
+                CleanupBudget::Entities(2),
+            )
+            .add_state_cleanup_budgeted::<_, CleanupLevel>(
+                AppState::Game,
+                CleanupBudget::Entities(2),
+            );
+        app.world.spawn(CleanupGame);
+        app.world.spawn(CleanupLevel);
+        app.world.spawn(CleanupLevel);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+    }
+}
@@ -0,0 +1,110 @@
+use std::any::TypeId;
+
+use bevy::prelude::*;
+
+use crate::{cleanup_entities, Cleanup, CleanupMarkers};
+
+/// Allows manually cleaning up all entities with a [`Cleanup`] component, outside of a state
+/// transition.
+///
+/// The cleanup behaves identically to a registered one: the [`CleanupAction`] of the first cleanup
+/// registered for the marker is applied (or [`CleanupAction::DespawnRecursive`] if there is none),
+/// [`CleanupHook`]s are run, and a [`CleanupReport`] is sent if the event has been added.
+///
+/// [`CleanupAction`]: crate::CleanupAction
+/// [`CleanupAction::DespawnRecursive`]: crate::CleanupAction::DespawnRecursive
+/// [`CleanupHook`]: crate::CleanupHook
+/// [`CleanupReport`]: crate::CleanupReport
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, CleanupCommandsExt};
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupLevel;
+///
+/// #[derive(Event)]
+/// struct RestartLevel;
+///
+/// fn restart_level(mut commands: Commands, mut restarts: EventReader<RestartLevel>) {
+///     if restarts.iter().count() > 0 {
+///         commands.cleanup::<CleanupLevel>();
+///     }
+/// }
+/// ```
+pub trait CleanupCommandsExt {
+    /// Cleans up all entities which have component `C`.
+    fn cleanup<C: Cleanup>(&mut self) -> &mut Self;
+}
+
+impl CleanupCommandsExt for Commands<'_, '_> {
+    fn cleanup<C: Cleanup>(&mut self) -> &mut Self {
+        self.add(|world: &mut World| {
+            world.cleanup::<C>();
+        });
+        self
+    }
+}
+
+impl CleanupCommandsExt for World {
+    fn cleanup<C: Cleanup>(&mut self) -> &mut Self {
+        let action = self
+            .get_resource::<CleanupMarkers>()
+            .and_then(|markers| markers.actions.get(&TypeId::of::<C>()).copied())
+            .unwrap_or_default();
+        let entities = self
+            .query_filtered::<Entity, With<C>>()
+            .iter(self)
+            .collect::<Vec<_>>();
+        cleanup_entities::<C>(self, entities, None, None, action);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::CleanupCommandsExt;
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup, CleanupAction};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupLevel;
+
+    #[test]
+    fn world_cleanup() {
+        let mut world = World::new();
+        world.spawn(CleanupGame);
+        world.spawn_empty();
+
+        world.cleanup::<CleanupGame>();
+        assert_eq!(1, world.entities().len());
+    }
+
+    #[test]
+    fn commands_cleanup_uses_registered_action() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup_with::<_, CleanupLevel>(AppState::Game, CleanupAction::RemoveMarker)
+            .add_systems(Update, |mut commands: Commands| {
+                commands.cleanup::<CleanupLevel>();
+            });
+        let entity = app.world.spawn(CleanupLevel).id();
+
+        app.update();
+        assert!(app.world.get_entity(entity).is_some());
+        assert!(!app.world.entity(entity).contains::<CleanupLevel>());
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::{app::AppExit, ecs::event::ManualEventReader, prelude::*};
+
+use crate::{budget, init_cleanup, Cleanup, CleanupAction, CleanupCommandsExt, CleanupMarkers};
+
+/// A [`Cleanup`] component which marks an entity to be cleaned up when the app exits.
+///
+/// This is only used by the [`CleanupOnExitPlugin`].
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct CleanupOnExit;
+
+impl Cleanup for CleanupOnExit {}
+
+/// Cleans up entities when an [`AppExit`] event is sent, so that teardown done in
+/// [`CleanupHook`]s (e.g. flushing files, or closing connections held in components) still happens
+/// when the app exits.
+///
+/// By default, only entities with [`CleanupOnExit`] are cleaned up. Use [`Self::all_markers`] to
+/// instead clean up every marker which has had a cleanup registered for it, in the order that they
+/// were first registered, using the action of their first registered cleanup.
+///
+/// The cleanup runs in the [`Last`] schedule of the frame in which [`AppExit`] was sent, before
+/// the app's runner stops, and after any budgeted cleanups have done their work for that frame.
+///
+/// [`CleanupHook`]: crate::CleanupHook
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{CleanupHook, CleanupOnExit, CleanupOnExitPlugin};
+///
+/// fn spawn_save_file(mut commands: Commands) {
+///     commands.spawn((
+///         Name::new("Save file"),
+///         CleanupOnExit,
+///         CleanupHook::new(|_, _| {
+///             // flush the save file here
+///         }),
+///     ));
+/// }
+///
+/// App::new()
+///     .add_plugins(CleanupOnExitPlugin::default())
+///     .add_systems(Startup, spawn_save_file);
+/// ```
+#[derive(Default)]
+pub struct CleanupOnExitPlugin {
+    all_markers: bool,
+}
+
+impl CleanupOnExitPlugin {
+    /// Creates a plugin which cleans up every registered marker when the app exits, not just
+    /// [`CleanupOnExit`].
+    pub fn all_markers() -> Self {
+        Self { all_markers: true }
+    }
+}
+
+impl Plugin for CleanupOnExitPlugin {
+    fn build(&self, app: &mut App) {
+        init_cleanup::<CleanupOnExit>(app, CleanupAction::default());
+        app.add_event::<AppExit>().add_systems(
+            Last,
+            cleanup_on_exit(self.all_markers).after(budget::run_budgeted_cleanups),
+        );
+    }
+}
+
+fn cleanup_on_exit(all_markers: bool) -> impl FnMut(&mut World, Local<ManualEventReader<AppExit>>) {
+    move |world: &mut World, mut reader: Local<ManualEventReader<AppExit>>| {
+        let events = world.resource::<Events<AppExit>>();
+        if reader.iter(events).count() == 0 {
+            return;
+        }
+
+        if !all_markers {
+            world.cleanup::<CleanupOnExit>();
+            return;
+        }
+        let cleanups = world.resource::<CleanupMarkers>().cleanups.clone();
+        for cleanup in cleanups {
+            cleanup(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{app::AppExit, prelude::*};
+
+    use super::{CleanupOnExit, CleanupOnExitPlugin};
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup, CleanupHook};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Default, Resource)]
+    struct Order(Vec<&'static str>);
+
+    fn app(plugin: CleanupOnExitPlugin) -> App {
+        let mut app = App::new();
+        app.init_resource::<Order>()
+            .add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_plugins(plugin);
+        let hook = |name| {
+            CleanupHook::new(move |_, world| {
+                world.resource_mut::<Order>().0.push(name);
+            })
+        };
+        app.world.spawn((CleanupGame, hook("game")));
+        app.world.spawn((CleanupOnExit, hook("exit")));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+
+        app.world.send_event(AppExit);
+        app.update();
+        app
+    }
+
+    #[test]
+    fn cleanup_on_exit_marker() {
+        let app = app(CleanupOnExitPlugin::default());
+        assert_eq!(vec!["exit"], app.world.resource::<Order>().0);
+        assert_eq!(1, app.world.entities().len());
+    }
+
+    #[test]
+    fn cleanup_all_markers() {
+        let app = app(CleanupOnExitPlugin::all_markers());
+        assert_eq!(vec!["game", "exit"], app.world.resource::<Order>().0);
+        assert_eq!(0, app.world.entities().len());
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{CleanupAction, CleanupKind, CleanupRegistry, CleanupTrigger};
+
+/// The serialized form of a [`CleanupRegistry`].
+#[derive(Serialize)]
+struct RegistryExport<'a> {
+    cleanups: Vec<ExportedCleanup<'a>>,
+    markers: BTreeMap<&'static str, Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct ExportedCleanup<'a> {
+    state: Option<&'static str>,
+    variant: Option<&'a str>,
+    marker: &'static str,
+    kind: CleanupKind,
+    action: &'static str,
+    schedule: &'a str,
+    trigger: &'a CleanupTrigger,
+}
+
+impl CleanupRegistry {
+    /// Serializes all registered cleanups to pretty-printed JSON.
+    ///
+    /// The output contains:
+    /// - `cleanups` - every registered cleanup, sorted by state, variant, schedule and marker
+    /// - `markers` - the type name of every marker, mapped to the state variants which it is
+    ///   cleaned up when exiting, e.g. `my_game::AppState::Game`. Resources are not included.
+    ///
+    /// Since the output is sorted, it can be checked into version control and diffed between
+    /// commits, e.g. to catch a state cleanup being removed by accident.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup, CleanupRegistry};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupGame;
+    ///
+    /// let mut app = App::new();
+    /// app.add_state::<AppState>()
+    ///     .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+    ///
+    /// let json = app.world.resource::<CleanupRegistry>().to_json().unwrap();
+    /// assert!(json.contains("\"schedule\": \"OnExit(Game)\""));
+    /// ```
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.export())
+    }
+
+    /// Serializes all registered cleanups to pretty-printed RON.
+    ///
+    /// The output has the same structure as [`Self::to_json`].
+    pub fn to_ron(&self) -> ron::Result<String> {
+        ron::ser::to_string_pretty(&self.export(), ron::ser::PrettyConfig::default())
+    }
+
+    fn export(&self) -> RegistryExport<'_> {
+        let mut cleanups = self
+            .iter()
+            .map(|cleanup| ExportedCleanup {
+                state: cleanup.state,
+                variant: cleanup.variant.as_deref(),
+                marker: cleanup.marker,
+                kind: cleanup.kind,
+                action: action_name(&cleanup.action),
+                schedule: &cleanup.schedule,
+                trigger: &cleanup.trigger,
+            })
+            .collect::<Vec<_>>();
+        cleanups.sort_by_key(|cleanup| {
+            (
+                cleanup.state,
+                cleanup.variant,
+                cleanup.schedule,
+                cleanup.marker,
+            )
+        });
+
+        let mut markers = BTreeMap::<_, Vec<_>>::new();
+        for cleanup in cleanups
+            .iter()
+            .filter(|cleanup| cleanup.kind == CleanupKind::Entities)
+        {
+            let variants = markers.entry(cleanup.marker).or_default();
+            if let (CleanupTrigger::OnExit, Some(state), Some(variant)) =
+                (cleanup.trigger, cleanup.state, cleanup.variant)
+            {
+                variants.push(format!("{}::{}", state, variant));
+            }
+        }
+
+        RegistryExport { cleanups, markers }
+    }
+}
+
+/// The name of the variant of `action`, which unlike its debug representation doesn't contain any
+/// function pointers, so it is the same between runs.
+fn action_name(action: &CleanupAction) -> &'static str {
+    match action {
+        CleanupAction::DespawnRecursive => "DespawnRecursive",
+        CleanupAction::Despawn => "Despawn",
+        CleanupAction::RemoveMarker => "RemoveMarker",
+        CleanupAction::RemoveBundle(_) => "RemoveBundle",
+        #[cfg(feature = "render")]
+        CleanupAction::Hide => "Hide",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup, CleanupAction, CleanupRegistry, CleanupResource};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Menu,
+        Game,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupMenu;
+
+    #[derive(Resource, CleanupResource)]
+    struct Level;
+
+    #[derive(Event)]
+    struct Restart;
+
+    fn registry() -> CleanupRegistry {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup_with::<_, CleanupGame>(AppState::Game, CleanupAction::Despawn)
+            .add_state_cleanup::<_, CleanupMenu>(AppState::Menu)
+            .add_state_enter_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_resource_cleanup::<_, Level>(AppState::Game)
+            .add_event_cleanup::<Restart, CleanupGame>();
+        app.world.remove_resource::<CleanupRegistry>().unwrap()
+    }
+
+    #[test]
+    fn export_json() {
+        let json: serde_json::Value = serde_json::from_str(&registry().to_json().unwrap()).unwrap();
+        let cleanups = json["cleanups"].as_array().unwrap();
+        assert_eq!(5, cleanups.len());
+        // cleanups which aren't tied to a state come first
+        assert!(cleanups[0]["state"].is_null());
+        assert!(cleanups[0]["trigger"]["OnEvent"]["event"]
+            .as_str()
+            .unwrap()
+            .ends_with("Restart"));
+        assert_eq!("Game", cleanups[1]["variant"]);
+        assert_eq!("OnEnter(Game)", cleanups[1]["schedule"]);
+        assert_eq!("Despawn", cleanups[2]["action"]);
+        assert_eq!("OnExit", cleanups[2]["trigger"]);
+        assert_eq!("Entities", cleanups[2]["kind"]);
+        assert_eq!("RemoveResource", cleanups[3]["kind"]);
+
+        let markers = json["markers"].as_object().unwrap();
+        assert_eq!(2, markers.len());
+        let game = markers
+            .iter()
+            .find(|(marker, _)| marker.ends_with("CleanupGame"))
+            .unwrap()
+            .1;
+        assert_eq!(1, game.as_array().unwrap().len());
+        assert!(game[0].as_str().unwrap().ends_with("AppState::Game"));
+    }
+
+    #[test]
+    fn export_ron() {
+        let ron = registry().to_ron().unwrap();
+        assert!(ron.contains("schedule: \"OnExit(Menu)\""));
+    }
+}
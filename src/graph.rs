@@ -0,0 +1,254 @@
+use std::fmt::Write;
+
+use bevy::{prelude::*, utils::get_short_name};
+
+use crate::{CleanupKind, CleanupRegistry, CleanupTrigger};
+
+/// Renders the state cleanups registered in `app` as a
+/// [DOT](https://graphviz.org/doc/info/lang.html) graph, which can be rendered using e.g.
+/// Graphviz's `dot -Tsvg`.
+///
+/// Each state type is drawn as a cluster containing all of its variants. Every [`Cleanup`] marker
+/// is drawn as a box, with an edge from each variant whose [`OnExit`] or [`OnEnter`] cleans it up.
+/// Transition cleanups are drawn as edges between the two variants, labelled with the markers
+/// which are cleaned up on that transition. State resources are drawn as cylinders, with an edge
+/// from each variant whose [`OnExit`] removes or resets them. Event and conditional cleanups are
+/// drawn as dotted edges from a diamond for their event or schedule.
+///
+/// The graph is built from the [`CleanupRegistry`], so only cleanups registered through
+/// [`AddStateCleanup`](crate::AddStateCleanup) are included.
+///
+/// [`Cleanup`]: crate::Cleanup
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{cleanup_graph_dot, Cleanup, AddStateCleanup};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     Game,
+/// }
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// let mut app = App::new();
+/// app.add_state::<AppState>()
+///     .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+///
+/// let dot = cleanup_graph_dot(&app);
+/// assert!(dot.starts_with("digraph"));
+/// ```
+pub fn cleanup_graph_dot(app: &App) -> String {
+    let mut dot = String::new();
+    // writing to a `String` never fails
+    let _ = write_graph(&mut dot, app.world.get_resource::<CleanupRegistry>());
+    dot
+}
+
+fn write_graph(dot: &mut String, registry: Option<&CleanupRegistry>) -> std::fmt::Result {
+    writeln!(dot, "digraph cleanup {{")?;
+    writeln!(dot, "    rankdir=LR;")?;
+    let Some(registry) = registry else {
+        return writeln!(dot, "}}");
+    };
+
+    for (index, (_, state, variants)) in registry.states.iter().enumerate() {
+        writeln!(dot, "    subgraph cluster_{} {{", index)?;
+        writeln!(dot, "        label={};", quote(&get_short_name(state)))?;
+        for variant in variants {
+            writeln!(
+                dot,
+                "        {} [label={}, shape=ellipse];",
+                quote(&format!("{}::{}", state, variant)),
+                quote(variant),
+            )?;
+        }
+        writeln!(dot, "    }}")?;
+    }
+
+    let mut markers = Vec::new();
+    for cleanup in registry.iter() {
+        let shape = match cleanup.kind {
+            CleanupKind::Entities => "box",
+            CleanupKind::RemoveResource | CleanupKind::ResetResource => "cylinder",
+        };
+        if !markers.contains(&(cleanup.marker, shape)) {
+            markers.push((cleanup.marker, shape));
+        }
+    }
+    for (marker, shape) in markers {
+        writeln!(
+            dot,
+            "    {} [label={}, shape={}];",
+            quote(marker),
+            quote(&get_short_name(marker)),
+            shape,
+        )?;
+    }
+
+    // cleanups which aren't tied to a state are drawn with an edge from their trigger instead
+    let mut triggers = Vec::new();
+    for cleanup in registry.iter() {
+        let trigger = match &cleanup.trigger {
+            CleanupTrigger::OnEvent { event } => *event,
+            CleanupTrigger::OnCondition => &cleanup.schedule,
+            _ => continue,
+        };
+        if !triggers.contains(&trigger) {
+            triggers.push(trigger);
+        }
+    }
+    for trigger in triggers {
+        writeln!(
+            dot,
+            "    {} [label={}, shape=diamond];",
+            quote(trigger),
+            quote(&get_short_name(trigger)),
+        )?;
+    }
+
+    let mut transitions = Vec::<(String, String, Vec<String>)>::new();
+    for cleanup in registry.iter() {
+        let state = cleanup.state.unwrap_or_default();
+        let variant = cleanup.variant.as_deref().unwrap_or_default();
+        let variant = format!("{}::{}", state, variant);
+        match &cleanup.trigger {
+            CleanupTrigger::OnExit => writeln!(
+                dot,
+                "    {} -> {} [label={}];",
+                quote(&variant),
+                quote(cleanup.marker),
+                quote(match cleanup.kind {
+                    CleanupKind::Entities => "OnExit",
+                    CleanupKind::RemoveResource => "OnExit: remove",
+                    CleanupKind::ResetResource => "OnExit: reset",
+                }),
+            )?,
+            CleanupTrigger::OnEnter => writeln!(
+                dot,
+                "    {} -> {} [label=\"OnEnter\", style=dashed];",
+                quote(&variant),
+                quote(cleanup.marker),
+            )?,
+            CleanupTrigger::OnEvent { event } => writeln!(
+                dot,
+                "    {} -> {} [label=\"OnEvent\", style=dotted];",
+                quote(event),
+                quote(cleanup.marker),
+            )?,
+            CleanupTrigger::OnCondition => writeln!(
+                dot,
+                "    {} -> {} [label=\"OnCondition\", style=dotted];",
+                quote(&cleanup.schedule),
+                quote(cleanup.marker),
+            )?,
+            CleanupTrigger::OnTransition { to } => {
+                let to = format!("{}::{}", state, to);
+                let marker = get_short_name(cleanup.marker);
+                match transitions
+                    .iter_mut()
+                    .find(|(from, other, _)| *from == variant && *other == to)
+                {
+                    Some((_, _, markers)) => markers.push(marker),
+                    None => transitions.push((variant, to, vec![marker])),
+                }
+            }
+        }
+    }
+    for (from, to, markers) in transitions {
+        writeln!(
+            dot,
+            "    {} -> {} [label={}, style=bold];",
+            quote(&from),
+            quote(&to),
+            quote(&format!("OnTransition: {}", markers.join(", "))),
+        )?;
+    }
+
+    writeln!(dot, "}}")
+}
+
+/// Quotes `id` so that it can be used as an ID or label in DOT.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::cleanup_graph_dot;
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup, CleanupResource};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Menu,
+        Game,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupMenu;
+
+    #[derive(Resource, CleanupResource, Default)]
+    struct Level;
+
+    #[derive(Event)]
+    struct Restart;
+
+    #[test]
+    fn graph() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_enter_cleanup::<_, CleanupMenu>(AppState::Menu)
+            .add_transition_cleanup::<_, CleanupGame>(AppState::Game, AppState::Menu)
+            .add_transition_cleanup::<_, CleanupMenu>(AppState::Game, AppState::Menu)
+            .add_state_resource_reset::<_, Level>(AppState::Game)
+            .add_event_cleanup::<Restart, CleanupGame>();
+        let dot = cleanup_graph_dot(&app);
+
+        let state = std::any::type_name::<AppState>();
+        let game = std::any::type_name::<CleanupGame>();
+        assert!(dot.starts_with("digraph cleanup {"));
+        assert!(dot.contains(&format!("\"{}::Menu\" [label=\"Menu\"", state)));
+        assert!(dot.contains(&format!("\"{}\" [label=\"CleanupGame\", shape=box]", game)));
+        assert!(dot.contains(&format!(
+            "\"{}::Game\" -> \"{}\" [label=\"OnExit\"]",
+            state, game
+        )));
+        assert!(dot.contains("[label=\"OnTransition: CleanupGame, CleanupMenu\", style=bold]"));
+        let level = std::any::type_name::<Level>();
+        assert!(dot.contains(&format!("\"{}\" [label=\"Level\", shape=cylinder]", level)));
+        assert!(dot.contains(&format!(
+            "\"{}::Game\" -> \"{}\" [label=\"OnExit: reset\"]",
+            state, level
+        )));
+        let restart = std::any::type_name::<Restart>();
+        assert!(dot.contains(&format!(
+            "\"{}\" [label=\"Restart\", shape=diamond]",
+            restart
+        )));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [label=\"OnEvent\", style=dotted]",
+            restart, game
+        )));
+    }
+
+    #[test]
+    fn empty_graph() {
+        assert_eq!(
+            "digraph cleanup {\n    rankdir=LR;\n}\n",
+            cleanup_graph_dot(&App::new())
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+/// A component which runs some logic on its entity right before the entity is cleaned up.
+///
+/// When a cleanup is applied to an entity with this component, the hook is invoked with the
+/// entity and the [`World`] before the [`CleanupAction`] is applied. This is useful for logic
+/// which must run before an entity dies, such as flushing stats, stopping audio or notifying
+/// the network.
+///
+/// If the action is [`CleanupAction::DespawnRecursive`], the hooks of all descendants of the entity
+/// are also run, since those will be despawned too.
+///
+/// Hooks run in a deterministic order: entities are cleaned up in order of their [`Entity`] ID,
+/// and for each entity, its own hook runs before the hooks of its descendants (which are visited
+/// depth-first, in [`Children`] order).
+///
+/// [`CleanupAction`]: crate::CleanupAction
+/// [`CleanupAction::DespawnRecursive`]: crate::CleanupAction::DespawnRecursive
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, CleanupHook};
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// #[derive(Resource, Default)]
+/// struct Stats {
+///     enemies_cleaned_up: usize,
+/// }
+///
+/// fn setup_game(mut commands: Commands) {
+///     commands.spawn((
+///         Name::new("Enemy"),
+///         CleanupGame,
+///         CleanupHook::new(|_, world| {
+///             world.resource_mut::<Stats>().enemies_cleaned_up += 1;
+///         }),
+///     ));
+/// }
+/// ```
+#[derive(Component)]
+pub struct CleanupHook(Box<HookFn>);
+
+type HookFn = dyn FnMut(Entity, &mut World) + Send + Sync;
+
+impl CleanupHook {
+    /// Creates a hook which runs `hook` when its entity is cleaned up.
+    pub fn new(hook: impl FnMut(Entity, &mut World) + Send + Sync + 'static) -> Self {
+        Self(Box::new(hook))
+    }
+}
+
+/// Runs the [`CleanupHook`] of `entity`, if it has one.
+///
+/// The hook is taken out of the entity while it runs, and put back afterwards if the entity still
+/// exists, since the cleanup action may not despawn it.
+pub(crate) fn run(world: &mut World, entity: Entity) {
+    let Some(mut hook) = world
+        .get_entity_mut(entity)
+        .and_then(|mut entity_mut| entity_mut.take::<CleanupHook>())
+    else {
+        return;
+    };
+
+    (hook.0)(entity, world);
+
+    if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+        entity_mut.insert(hook);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::CleanupHook;
+    use crate as bevy_cleanup;
+    use crate::{Cleanup, CleanupAction};
+
+    #[derive(Component, Cleanup)]
+    struct CleanupTest;
+
+    #[derive(Resource, Default)]
+    struct Order(Vec<&'static str>);
+
+    fn hook(name: &'static str) -> CleanupHook {
+        CleanupHook::new(move |_, world| {
+            world.resource_mut::<Order>().0.push(name);
+        })
+    }
+
+    #[test]
+    fn hooks_run_before_despawn() {
+        let mut world = World::new();
+        world.init_resource::<Order>();
+        let parent = world.spawn((CleanupTest, hook("parent"))).id();
+        let child = world.spawn(hook("child")).set_parent(parent).id();
+        world.spawn(hook("grandchild")).set_parent(child);
+        world.spawn(hook("sibling")).set_parent(parent);
+
+        CleanupAction::DespawnRecursive.apply::<CleanupTest>(&mut world, parent);
+        assert_eq!(
+            vec!["parent", "child", "grandchild", "sibling"],
+            world.resource::<Order>().0
+        );
+        assert_eq!(0, world.query::<&CleanupHook>().iter(&world).count());
+    }
+
+    #[test]
+    fn hook_kept_if_not_despawned() {
+        let mut world = World::new();
+        world.init_resource::<Order>();
+        let entity = world.spawn((CleanupTest, hook("entity"))).id();
+
+        CleanupAction::RemoveMarker.apply::<CleanupTest>(&mut world, entity);
+        assert_eq!(vec!["entity"], world.resource::<Order>().0);
+        assert!(world.entity(entity).contains::<CleanupHook>());
+    }
+}
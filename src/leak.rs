@@ -0,0 +1,303 @@
+use std::{any::TypeId, marker::PhantomData};
+
+use bevy::{
+    ecs::schedule::{apply_state_transition, run_enter_schedule},
+    prelude::*,
+    utils::HashSet,
+};
+
+use crate::{CleanupAfter, CleanupKind, CleanupRegistry, CleanupTrigger};
+
+/// A component which marks an entity as intentionally outliving the state it was spawned in.
+///
+/// This is only used by the [`CleanupLeakDetectorPlugin`], which will not report entities with
+/// this component, or with an ancestor which has this component, as leaks.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct Persistent;
+
+/// Detects entities which were spawned during a variant of the state `S`, but will not be cleaned
+/// up when that variant is exited.
+///
+/// The most common cause of this is forgetting to add a cleanup marker to an entity when spawning
+/// it. When a variant of `S` is exited, all entities which were spawned while that variant was
+/// active are checked, and any entity which:
+/// - doesn't have a [`Cleanup`] component which is cleaned up when exiting that variant, according
+///   to the [`CleanupRegistry`]
+/// - isn't [`Persistent`], and doesn't have a [`CleanupAfter`] which will clean it up later
+/// - doesn't have an ancestor which matches either of the above
+///
+/// is reported as a leak. By default a warning is logged, but you can use [`Self::panicking`] to
+/// panic instead, which is useful in tests.
+///
+/// [`Cleanup`]: crate::Cleanup
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, AddStateCleanup, CleanupLeakDetectorPlugin, Persistent};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     Game,
+/// }
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// fn setup_game(mut commands: Commands) {
+///     commands.spawn((Name::new("Player"), CleanupGame));
+///     // this is fine, since we want the music to keep playing after the game
+///     commands.spawn((Name::new("Music"), Persistent));
+///     // this will be reported as a leak when exiting `AppState::Game`
+///     commands.spawn(Name::new("Enemy"));
+/// }
+///
+/// App::new()
+///     .add_state::<AppState>()
+///     .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+///     .add_plugins(CleanupLeakDetectorPlugin::<AppState>::default())
+///     .add_systems(OnEnter(AppState::Game), setup_game);
+/// ```
+pub struct CleanupLeakDetectorPlugin<S: States> {
+    panic: bool,
+    _state: PhantomData<S>,
+}
+
+impl<S: States> Default for CleanupLeakDetectorPlugin<S> {
+    fn default() -> Self {
+        Self {
+            panic: false,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<S: States> CleanupLeakDetectorPlugin<S> {
+    /// Creates a leak detector which panics when a leak is detected, instead of logging a warning.
+    pub fn panicking() -> Self {
+        Self {
+            panic: true,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<S: States> Plugin for CleanupLeakDetectorPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LeakDetector::<S> {
+            panic: self.panic,
+            known: None,
+            _state: PhantomData,
+        });
+
+        // this runs before any `OnEnter` system, so that entities spawned directly into the world
+        // by those systems aren't treated as already existing
+        app.add_systems(
+            StateTransition,
+            detect_leaks::<S>
+                .before(run_enter_schedule::<S>)
+                .before(apply_state_transition::<S>),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct LeakDetector<S: States> {
+    panic: bool,
+    /// The entities which already existed when the current state was entered, or `None` if the
+    /// initial state hasn't been entered yet.
+    known: Option<HashSet<Entity>>,
+    _state: PhantomData<S>,
+}
+
+fn detect_leaks<S: States>(world: &mut World) {
+    let exited = world.resource::<State<S>>().get();
+    let entered = match &world.resource::<NextState<S>>().0 {
+        Some(entered) if entered != exited => Some(entered),
+        _ => None,
+    };
+
+    let detector = world.resource::<LeakDetector<S>>();
+    match (&detector.known, entered) {
+        (Some(known), Some(entered)) => report_leaks(world, known, exited, entered, detector.panic),
+        (Some(_), None) => return,
+        (None, _) => {}
+    }
+
+    let known = world.iter_entities().map(|entity| entity.id()).collect();
+    world.resource_mut::<LeakDetector<S>>().known = Some(known);
+}
+
+fn report_leaks<S: States>(
+    world: &World,
+    known: &HashSet<Entity>,
+    exited: &S,
+    entered: &S,
+    panic: bool,
+) {
+    // the markers which are cleaned up when going from `exited` to `entered`
+    let entered = format!("{:?}", entered);
+    let markers = world
+        .get_resource::<CleanupRegistry>()
+        .into_iter()
+        .flat_map(|registry| registry.for_variant(exited))
+        .filter(|cleanup| cleanup.kind == CleanupKind::Entities)
+        .filter(|cleanup| match &cleanup.trigger {
+            CleanupTrigger::OnExit => true,
+            CleanupTrigger::OnEnter
+            | CleanupTrigger::OnEvent { .. }
+            | CleanupTrigger::OnCondition => false,
+            CleanupTrigger::OnTransition { to } => *to == entered,
+        })
+        .map(|cleanup| cleanup.marker_id)
+        .collect::<Vec<TypeId>>();
+    let is_covered = |entity: EntityRef| {
+        entity.contains::<Persistent>()
+            || entity.contains::<CleanupAfter>()
+            || markers
+                .iter()
+                .any(|&marker| entity.contains_type_id(marker))
+    };
+
+    let mut leaks = world
+        .iter_entities()
+        .filter(|entity| !known.contains(&entity.id()))
+        .filter(|entity| {
+            let mut current = *entity;
+            loop {
+                if is_covered(current) {
+                    return false;
+                }
+                // a parent which was despawned without its children makes this the root
+                match current
+                    .get::<Parent>()
+                    .and_then(|parent| world.get_entity(parent.get()))
+                {
+                    Some(parent) => current = parent,
+                    None => return true,
+                }
+            }
+        })
+        .map(|entity| match entity.get::<Name>() {
+            Some(name) => format!("{:?} ({})", entity.id(), name),
+            None => format!("{:?}", entity.id()),
+        })
+        .collect::<Vec<_>>();
+
+    if leaks.is_empty() {
+        return;
+    }
+    leaks.sort();
+
+    let message = format!(
+        "{} entities spawned during {:?} will not be cleaned up when exiting it: {}",
+        leaks.len(),
+        exited,
+        leaks.join(", "),
+    );
+    if panic {
+        panic!("{}", message);
+    } else {
+        warn!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{CleanupLeakDetectorPlugin, Persistent};
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Menu,
+        Game,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupMenu;
+
+    fn app<M>(setup_game: impl IntoSystemConfigs<M>) -> App {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_cleanup::<_, CleanupMenu>(AppState::Menu)
+            .add_plugins(CleanupLeakDetectorPlugin::<AppState>::panicking())
+            .add_systems(OnEnter(AppState::Game), setup_game);
+
+        app.world.spawn(Name::new("Spawned before the game"));
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        app
+    }
+
+    #[test]
+    fn no_leaks() {
+        let mut app = app(|mut commands: Commands| {
+            commands.spawn(CleanupGame).with_children(|parent| {
+                parent.spawn_empty();
+            });
+            commands.spawn(Persistent);
+        });
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+    }
+
+    #[test]
+    #[should_panic(expected = "Leaked")]
+    fn leak() {
+        let mut app = app(|mut commands: Commands| {
+            commands.spawn(Name::new("Leaked"));
+        });
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+    }
+
+    #[test]
+    #[should_panic(expected = "Leaked")]
+    fn leak_spawned_in_world() {
+        let mut app = app(|world: &mut World| {
+            world.spawn(Name::new("Leaked"));
+        });
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+    }
+
+    #[test]
+    #[should_panic(expected = "Leaked")]
+    fn leak_with_other_variant_marker() {
+        let mut app = app(|mut commands: Commands| {
+            commands.spawn((Name::new("Leaked"), CleanupMenu));
+        });
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+    }
+
+    #[test]
+    #[should_panic(expected = "Leaked")]
+    fn leak_with_despawned_parent() {
+        let mut app = app(|mut commands: Commands| {
+            let parent = commands.spawn_empty().id();
+            commands.spawn(Name::new("Leaked")).set_parent(parent);
+            commands.entity(parent).despawn();
+        });
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+    }
+}
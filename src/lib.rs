@@ -2,96 +2,1077 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
-use bevy::prelude::*;
+use std::{
+    any::{type_name, TypeId},
+    fmt::Debug,
+};
 
+use bevy::{
+    ecs::{event::ManualEventReader, schedule::ScheduleLabel},
+    prelude::*,
+    utils::{HashMap, Instant},
+};
+
+mod action;
+mod budget;
+mod commands;
+mod exit;
+#[cfg(feature = "serde")]
+mod export;
+mod graph;
+mod hook;
+mod leak;
+mod lifetime;
+mod mode;
+mod propagate;
+mod registry;
+mod report;
+mod set;
+mod spawn;
+mod stats;
+
+pub use action::*;
+pub use budget::*;
+pub use commands::*;
+pub use exit::*;
+pub use graph::*;
+pub use hook::*;
+pub use leak::*;
+pub use lifetime::*;
+pub use mode::*;
+pub use registry::*;
+pub use report::*;
+pub use set::*;
+pub use spawn::*;
+pub use stats::*;
+
+#[cfg(feature = "derive")]
+pub use bevy_cleanup_derive::{Cleanup, CleanupResource};
+
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use inventory;
+}
+
+/// The trait used to denote that a component is a "cleanup marker".
+///
+/// This trait is never queried for by itself, but is a bound for
+/// [`AddStateCleanup::add_state_cleanup`], just to make sure you don't accidentally mark the wrong
+/// component as a cleanup component.
+///
+/// This marker trait is typically automatically derived using the `Cleanup` derive macro (it's
+/// simply an implementation block, which also implements [`Cleanup::marker`] for unit structs).
+/// You would typically keep your `States` enum and all `Cleanup`-deriving types close together.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::Cleanup;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     Game,
+/// }
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupMenu;
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+/// ```
+pub trait Cleanup: Component {
+    /// Creates a value of this marker, which is used to insert it into entities spawned with
+    /// [`SpawnScopedExt::spawn_scoped`].
+    ///
+    /// By default, this returns `None`, meaning that the marker can't be inserted automatically.
+    /// The `Cleanup` derive macro implements this for unit structs.
+    fn marker() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// The trait used to denote that a resource is specific to a state, and should be cleaned up
+/// when that state is exited.
+///
+/// This is the resource equivalent of [`Cleanup`], and is a bound for
+/// [`AddStateCleanup::add_state_resource_cleanup`] and
+/// [`AddStateCleanup::add_state_resource_reset`].
+///
+/// This marker trait is typically automatically derived using the `CleanupResource` derive macro.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::CleanupResource;
+///
+/// #[derive(Resource, CleanupResource)]
+/// struct LevelData {
+///     seed: u64,
+/// }
+///
+/// #[derive(Resource, CleanupResource, Default)]
+/// struct MenuSelection(usize);
+/// ```
+pub trait CleanupResource: Resource {}
+
+/// A [`Cleanup`] component which marks an entity as belonging to a specific variant of the state
+/// `S`.
+///
+/// Instead of defining a separate marker type for every state variant, you can use this single
+/// component type for every variant of your `States` enum. Once
+/// [`AddStateCleanup::add_state_scoped_cleanup`] has been called for `S`, entities with this
+/// component will be recursively despawned when exiting the variant that they store.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{StateScoped, AddStateCleanup};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     Game,
+/// }
+///
+/// fn setup_game(mut commands: Commands) {
+///     commands.spawn((
+///         Name::new("Player"),
+///         StateScoped(AppState::Game),
+///     ));
+/// }
+///
+/// App::new()
+///     .add_state::<AppState>()
+///     .add_state_scoped_cleanup::<AppState>()
+///     .add_systems(OnEnter(AppState::Game), setup_game);
+/// ```
+#[derive(Debug, Clone, Component)]
+pub struct StateScoped<S: States>(pub S);
+
+impl<S: States> Cleanup for StateScoped<S> {}
+
+/// Allows using [`Self::add_state_cleanup`].
+///
+/// All state cleanup systems added by this trait are put in the [`CleanupSet::despawn`] set of
+/// their state, so your own systems can be ordered against them.
+pub trait AddStateCleanup {
+    /// When the state `variant` is exited ([`OnExit`]), all entities which have component `C`
+    /// will be recursively despawned.
+    /// 
+    /// You do not have to add a state cleanup to *every* `S` variant, just the ones you want to
+    /// despawn entities for when you exit that variant. So if you know you won't be adding any
+    /// entities during a state (e.g. a `LoadGame` state), then don't bother adding state cleanup
+    /// to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupMenu;
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupGame;
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .add_state_cleanup::<_, CleanupMenu>(AppState::Menu)
+    ///     .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+    /// ```
+    fn add_state_cleanup<S: States, C: Cleanup>(&mut self, variant: S) -> &mut Self;
+
+    /// Like [`Self::add_state_cleanup`], but applies `action` to the entities instead of
+    /// recursively despawning them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, CleanupAction, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupGame;
+    ///
+    /// #[derive(Component)]
+    /// struct Controllable;
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     // keep the entities around, but stop them from being controlled
+    ///     .add_state_cleanup_with::<_, CleanupGame>(
+    ///         AppState::Game,
+    ///         CleanupAction::remove::<Controllable>(),
+    ///     );
+    /// ```
+    fn add_state_cleanup_with<S: States, C: Cleanup>(
+        &mut self,
+        variant: S,
+        action: CleanupAction,
+    ) -> &mut Self;
+
+    /// When the state `variant` is exited ([`OnExit`]), all entities which have component `C` will
+    /// be recursively despawned over multiple frames.
+    ///
+    /// Despawning a huge amount of entities at once can cause a visible hitch. Instead, when the
+    /// cleanup starts, all entities are given [`CleanupPending`], and are then cleaned up in the
+    /// [`Last`] schedule of the following frames, doing at most `budget` worth of work per frame.
+    /// While this is happening, the [`CleanupInProgress`] resource lists the remaining entities,
+    /// and the [`cleanup_in_progress`] run condition is `true`.
+    ///
+    /// The [`CleanupReport`] of the cleanup is sent once all of its entities are cleaned up.
+    ///
+    /// Since the entities are cleaned up in later frames, they still exist when the
+    /// [`CleanupSet::after`] set of the [`OnExit`] schedule runs. Use the [`cleanup_in_progress`]
+    /// run condition to wait until they are gone instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{cleanup_in_progress, Cleanup, AddStateCleanup, CleanupBudget};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupGame;
+    ///
+    /// fn show_loading_screen() {}
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .add_state_cleanup_budgeted::<_, CleanupGame>(
+    ///         AppState::Game,
+    ///         CleanupBudget::Entities(1000),
+    ///     )
+    ///     .add_systems(Update, show_loading_screen.run_if(cleanup_in_progress));
+    /// ```
+    fn add_state_cleanup_budgeted<S: States, C: Cleanup>(
+        &mut self,
+        variant: S,
+        budget: CleanupBudget,
+    ) -> &mut Self;
+
+    /// When the state `variant` is entered ([`OnEnter`]), all entities which have component `C`
+    /// will be recursively despawned.
+    ///
+    /// This can be used to wipe stale entities left over from a previous run of the same state
+    /// (e.g. ones spawned by a system which ran late), before the new state's setup runs.
+    ///
+    /// The cleanup isn't ordered against your own `OnEnter` systems, so entities which they spawn
+    /// directly into the [`World`] (e.g. from an exclusive system) may be cleaned up straight
+    /// away. To avoid this, order those systems after the [`CleanupSet`] of `S`, as shown below.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup, CleanupSet};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupGame;
+    ///
+    /// fn setup_game(world: &mut World) {
+    ///     world.spawn((Name::new("Player"), CleanupGame));
+    /// }
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+    ///     .add_state_enter_cleanup::<_, CleanupGame>(AppState::Game)
+    ///     .add_systems(
+    ///         OnEnter(AppState::Game),
+    ///         setup_game.after(CleanupSet::<AppState>::new()),
+    ///     );
+    /// ```
+    fn add_state_enter_cleanup<S: States, C: Cleanup>(&mut self, variant: S) -> &mut Self;
+
+    /// Like [`Self::add_state_enter_cleanup`], but applies `action` to the entities instead of
+    /// recursively despawning them.
+    fn add_state_enter_cleanup_with<S: States, C: Cleanup>(
+        &mut self,
+        variant: S,
+        action: CleanupAction,
+    ) -> &mut Self;
+
+    /// When the state transitions from the variant `from` to the variant `to` ([`OnTransition`]),
+    /// all entities which have component `C` will be recursively despawned.
+    ///
+    /// Unlike [`Self::add_state_cleanup`], this only runs for this specific transition. This is
+    /// useful when you want to keep entities around for some transitions out of a state, but not
+    /// others - e.g. keeping the level loaded when going from `Game` to `Pause`, but despawning it
+    /// when going from `Game` to `Menu`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    ///     Pause,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupLevel;
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .add_transition_cleanup::<_, CleanupLevel>(AppState::Game, AppState::Menu)
+    ///     .add_transition_cleanup::<_, CleanupLevel>(AppState::Pause, AppState::Menu);
+    /// ```
+    fn add_transition_cleanup<S: States, C: Cleanup>(&mut self, from: S, to: S) -> &mut Self;
+
+    /// Like [`Self::add_transition_cleanup`], but applies `action` to the entities instead of
+    /// recursively despawning them.
+    fn add_transition_cleanup_with<S: States, C: Cleanup>(
+        &mut self,
+        from: S,
+        to: S,
+        action: CleanupAction,
+    ) -> &mut Self;
+
+    /// When the state `variant` is exited ([`OnExit`]), the resource `R` will be removed.
+    ///
+    /// If the resource does not exist when the state is exited, nothing happens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{CleanupResource, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Resource, CleanupResource)]
+    /// struct LevelData {
+    ///     seed: u64,
+    /// }
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .add_state_resource_cleanup::<_, LevelData>(AppState::Game);
+    /// ```
+    fn add_state_resource_cleanup<S: States, R: CleanupResource>(
+        &mut self,
+        variant: S,
+    ) -> &mut Self;
+
+    /// When the state `variant` is exited ([`OnExit`]), the resource `R` will be reset to its
+    /// [`Default`] value.
+    ///
+    /// Unlike [`Self::add_state_resource_cleanup`], the resource is always present after the state
+    /// is exited, even if it did not exist before.
+    fn add_state_resource_reset<S: States, R: CleanupResource + Default>(
+        &mut self,
+        variant: S,
+    ) -> &mut Self;
+
+    /// When any variant of the state `S` is exited ([`OnExit`]), all entities which have a
+    /// [`StateScoped<S>`] component storing that variant will be recursively despawned.
+    ///
+    /// See [`StateScoped`] for an example.
+    fn add_state_scoped_cleanup<S: States>(&mut self) -> &mut Self;
+
+    /// Whenever at least one event `E` is sent, all entities which have component `C` will be
+    /// cleaned up.
+    ///
+    /// Like a manual cleanup using [`CleanupCommandsExt`], this applies the [`CleanupAction`] of
+    /// the first cleanup registered for `C`, or recursively despawns the entities if this is the
+    /// first one.
+    ///
+    /// The events are read in [`PostUpdate`], so events sent during [`Update`] trigger a cleanup in
+    /// the same frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Event)]
+    /// struct RoundEnded;
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupRound;
+    ///
+    /// App::new()
+    ///     .add_event::<RoundEnded>()
+    ///     .add_event_cleanup::<RoundEnded, CleanupRound>();
+    /// ```
+    fn add_event_cleanup<E: Event, C: Cleanup>(&mut self) -> &mut Self;
+
+    /// Like [`Self::add_event_cleanup`], but only events for which `filter` returns `true` trigger
+    /// a cleanup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Event)]
+    /// struct LevelUnloaded {
+    ///     level: usize,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupTutorial;
+    ///
+    /// App::new()
+    ///     .add_event::<LevelUnloaded>()
+    ///     .add_event_cleanup_filtered::<LevelUnloaded, CleanupTutorial>(|event| event.level == 0);
+    /// ```
+    fn add_event_cleanup_filtered<E: Event, C: Cleanup>(
+        &mut self,
+        filter: impl Fn(&E) -> bool + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Adds a cleanup of all entities which have component `C` to `schedule`, which only runs if
+    /// `condition` is met.
+    ///
+    /// This is a shorthand for adding [`cleanup_system`] with a run condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Resource)]
+    /// struct Difficulty(f32);
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupEnemies;
+    ///
+    /// App::new()
+    ///     .insert_resource(Difficulty(1.0))
+    ///     .add_conditional_cleanup::<CleanupEnemies, _>(
+    ///         Update,
+    ///         resource_changed::<Difficulty>(),
+    ///     );
+    /// ```
+    fn add_conditional_cleanup<C: Cleanup, M>(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        condition: impl Condition<M>,
+    ) -> &mut Self;
+
+    /// While the state `variant` is active, [`CleanupAfter`] timers are paused.
+    ///
+    /// This adds a run condition to the [`CleanupAfterSet`], so it can be called multiple times to
+    /// pause the timers in multiple variants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{AddStateCleanup, CleanupAfterPlugin};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Game,
+    ///     Pause,
+    /// }
+    ///
+    /// App::new()
+    ///     .add_plugins((MinimalPlugins, CleanupAfterPlugin))
+    ///     .add_state::<AppState>()
+    ///     .pause_cleanup_after(AppState::Pause);
+    /// ```
+    fn pause_cleanup_after<S: States>(&mut self, variant: S) -> &mut Self;
+
+    /// Keeps the cleanup marker `C` in sync down the hierarchy, so that descendants of an entity
+    /// with `C` get their own copy of `C`.
+    ///
+    /// Without this, an entity which is spawned as a child of an entity with `C` is only cleaned up
+    /// because of its position in the hierarchy when the cleanup runs - if it is reparented to an
+    /// entity which isn't cleaned up, it silently escapes the cleanup. With this, an entity
+    /// inherits `C` whenever its [`Parent`] changes to a descendant of an entity with `C`, or when
+    /// one of its ancestors is given `C`, and keeps it when reparented later on.
+    ///
+    /// Markers are propagated in [`PostUpdate`], and this must be called for each marker type
+    /// which should be propagated. Entities which are [`KeepOnCleanup`] or [`Persistent`], along
+    /// with their descendants, are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Clone, Component, Cleanup)]
+    /// struct CleanupGame;
+    ///
+    /// fn setup_game(mut commands: Commands) {
+    ///     commands.spawn(CleanupGame).with_children(|parent| {
+    ///         // gets `CleanupGame` once it is propagated
+    ///         parent.spawn(Name::new("Weapon"));
+    ///     });
+    /// }
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+    ///     .propagate_cleanup_marker::<CleanupGame>()
+    ///     .add_systems(OnEnter(AppState::Game), setup_game);
+    /// ```
+    fn propagate_cleanup_marker<C: Cleanup + Clone>(&mut self) -> &mut Self;
+
+    /// Panics instead of logging a warning when a duplicate or conflicting cleanup is registered.
+    ///
+    /// See [`CleanupRegistry`] for which registrations are checked. This only affects cleanups
+    /// registered after this is called, so call it before registering any cleanups. This is
+    /// useful in tests.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupGame;
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .panic_on_cleanup_conflicts()
+    ///     .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+    ///     // panics, since this cleanup was already registered
+    ///     .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+    /// ```
+    fn panic_on_cleanup_conflicts(&mut self) -> &mut Self;
+
+    /// Allows the marker `C` to be cleaned up when exiting multiple variants of the same state,
+    /// without it being reported as a conflict by the [`CleanupRegistry`].
+    ///
+    /// This must be called before the cleanups for `C` are registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    ///     GameOver,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// struct CleanupHud;
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     .allow_multiple_cleanup_variants::<CleanupHud>()
+    ///     .add_state_cleanup::<_, CleanupHud>(AppState::Game)
+    ///     .add_state_cleanup::<_, CleanupHud>(AppState::GameOver);
+    /// ```
+    fn allow_multiple_cleanup_variants<C: Cleanup>(&mut self) -> &mut Self;
+
+    /// Adds the state cleanup of every [`Cleanup`] type which was annotated with a
+    /// `#[cleanup(state = ...)]` attribute for a variant of `S`, anywhere in the program.
+    ///
+    /// This is equivalent to calling [`Self::add_state_cleanup`] for every one of those types
+    /// yourself, so you don't have to remember to register each new marker type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// #[cleanup(state = AppState::Menu)]
+    /// struct CleanupMenu;
+    ///
+    /// #[derive(Component, Cleanup)]
+    /// #[cleanup(state = AppState::Game)]
+    /// struct CleanupGame;
+    ///
+    /// App::new()
+    ///     .add_state::<AppState>()
+    ///     // equivalent to adding the state cleanup of `CleanupMenu` and `CleanupGame`
+    ///     .add_cleanups::<AppState>();
+    /// ```
+    #[cfg(feature = "derive")]
+    fn add_cleanups<S: States>(&mut self) -> &mut Self;
+}
+
+impl AddStateCleanup for App {
+    fn add_state_cleanup<S: States, C: Cleanup>(&mut self, variant: S) -> &mut Self {
+        self.add_state_cleanup_with::<S, C>(variant, CleanupAction::default())
+    }
+
+    fn add_state_cleanup_with<S: States, C: Cleanup>(
+        &mut self,
+        variant: S,
+        action: CleanupAction,
+    ) -> &mut Self {
+        let schedule = OnExit(variant.clone());
+        if !register_cleanup::<S, C>(self, &variant, &schedule, CleanupTrigger::OnExit, action) {
+            return self;
+        }
+        register_scoped_marker::<S, C>(self, variant);
+        init_cleanup::<C>(self, action);
+        let cleanup = state_cleanup::<S, C>(&schedule, action);
+        add_cleanup_systems::<S, _>(self, schedule, cleanup);
+        self
+    }
+
+    fn add_state_cleanup_budgeted<S: States, C: Cleanup>(
+        &mut self,
+        variant: S,
+        budget: CleanupBudget,
+    ) -> &mut Self {
+        let action = CleanupAction::default();
+        let trigger = CleanupTrigger::OnExit;
+        if !register_cleanup::<S, C>(self, &variant, &OnExit(variant.clone()), trigger, action) {
+            return self;
+        }
+        register_scoped_marker::<S, C>(self, variant.clone());
+        let schedule = format!("{:?}", OnExit(variant.clone()));
+        init_cleanup::<C>(self, action);
+        if !self.world.contains_resource::<CleanupInProgress>() {
+            self.init_resource::<CleanupInProgress>()
+                .add_systems(Last, budget::run_budgeted_cleanups);
+        }
+        let cleanup =
+            budget::start_budgeted_cleanup::<C>(Some(type_name::<S>()), schedule, action, budget);
+        add_cleanup_systems::<S, _>(self, OnExit(variant), cleanup);
+        self
+    }
+
+    fn add_state_enter_cleanup<S: States, C: Cleanup>(&mut self, variant: S) -> &mut Self {
+        self.add_state_enter_cleanup_with::<S, C>(variant, CleanupAction::default())
+    }
+
+    fn add_state_enter_cleanup_with<S: States, C: Cleanup>(
+        &mut self,
+        variant: S,
+        action: CleanupAction,
+    ) -> &mut Self {
+        let schedule = OnEnter(variant.clone());
+        if !register_cleanup::<S, C>(self, &variant, &schedule, CleanupTrigger::OnEnter, action) {
+            return self;
+        }
+        init_cleanup::<C>(self, action);
+        let cleanup = state_cleanup::<S, C>(&schedule, action);
+        add_cleanup_systems::<S, _>(self, schedule, cleanup);
+        self
+    }
+
+    fn add_transition_cleanup<S: States, C: Cleanup>(&mut self, from: S, to: S) -> &mut Self {
+        self.add_transition_cleanup_with::<S, C>(from, to, CleanupAction::default())
+    }
+
+    fn add_transition_cleanup_with<S: States, C: Cleanup>(
+        &mut self,
+        from: S,
+        to: S,
+        action: CleanupAction,
+    ) -> &mut Self {
+        let schedule = OnTransition {
+            from: from.clone(),
+            to,
+        };
+        let trigger = CleanupTrigger::OnTransition {
+            to: format!("{:?}", schedule.to),
+        };
+        if !register_cleanup::<S, C>(self, &from, &schedule, trigger, action) {
+            return self;
+        }
+        init_cleanup::<C>(self, action);
+        let cleanup = state_cleanup::<S, C>(&schedule, action);
+        add_cleanup_systems::<S, _>(self, schedule, cleanup);
+        self
+    }
+
+    fn add_state_resource_cleanup<S: States, R: CleanupResource>(
+        &mut self,
+        variant: S,
+    ) -> &mut Self {
+        let schedule = OnExit(variant.clone());
+        let kind = CleanupKind::RemoveResource;
+        if !register_resource_cleanup::<S, R>(self, &variant, &schedule, kind) {
+            return self;
+        }
+        let schedule_name = format!("{:?}", schedule);
+        add_cleanup_systems::<S, _>(self, schedule, move |world: &mut World| {
+            let (resource, state) = (type_name::<R>(), type_name::<S>());
+            if !mode::dry_run_resource(world, "remove", resource, state, &schedule_name) {
+                world.remove_resource::<R>();
+            }
+        });
+        self
+    }
+
+    fn add_state_resource_reset<S: States, R: CleanupResource + Default>(
+        &mut self,
+        variant: S,
+    ) -> &mut Self {
+        let schedule = OnExit(variant.clone());
+        let kind = CleanupKind::ResetResource;
+        if !register_resource_cleanup::<S, R>(self, &variant, &schedule, kind) {
+            return self;
+        }
+        let schedule_name = format!("{:?}", schedule);
+        add_cleanup_systems::<S, _>(self, schedule, move |world: &mut World| {
+            let (resource, state) = (type_name::<R>(), type_name::<S>());
+            if !mode::dry_run_resource(world, "reset", resource, state, &schedule_name) {
+                world.insert_resource(R::default());
+            }
+        });
+        self
+    }
+
+    fn add_state_scoped_cleanup<S: States>(&mut self) -> &mut Self {
+        init_cleanup::<StateScoped<S>>(self, CleanupAction::default());
+        self.allow_multiple_cleanup_variants::<StateScoped<S>>();
+        for variant in S::variants() {
+            let schedule = OnExit(variant.clone());
+            let trigger = CleanupTrigger::OnExit;
+            let action = CleanupAction::default();
+            if !register_cleanup::<S, StateScoped<S>>(self, &variant, &schedule, trigger, action) {
+                continue;
+            }
+            let schedule_name = format!("{:?}", schedule);
+            let cleanup = move |world: &mut World| {
+                let entities = world
+                    .query::<(Entity, &StateScoped<S>)>()
+                    .iter(world)
+                    .filter(|(_, scoped)| scoped.0 == variant)
+                    .map(|(entity, _)| entity)
+                    .collect::<Vec<_>>();
+                cleanup_entities::<StateScoped<S>>(
+                    world,
+                    entities,
+                    Some(type_name::<S>()),
+                    Some(&schedule_name),
+                    CleanupAction::default(),
+                );
+            };
+
+            add_cleanup_systems::<S, _>(self, schedule, cleanup);
+        }
+        self
+    }
+
+    fn add_event_cleanup<E: Event, C: Cleanup>(&mut self) -> &mut Self {
+        self.add_event_cleanup_filtered::<E, C>(|_| true)
+    }
+
+    fn add_event_cleanup_filtered<E: Event, C: Cleanup>(
+        &mut self,
+        filter: impl Fn(&E) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        init_cleanup::<C>(self, CleanupAction::default());
+        // use the same action as manual cleanups of `C`
+        let action = self.world.resource::<CleanupMarkers>().actions[&TypeId::of::<C>()];
+        let schedule = format!("{:?}", PostUpdate);
+        let cleanup = move |world: &mut World, mut reader: Local<ManualEventReader<E>>| {
+            let triggered = reader
+                .iter(world.resource::<Events<E>>())
+                .filter(|event| filter(event))
+                .count()
+                > 0;
+            if !triggered {
+                return;
+            }
+
+            let entities = world
+                .query_filtered::<Entity, With<C>>()
+                .iter(world)
+                .collect::<Vec<_>>();
+            cleanup_entities::<C>(world, entities, None, Some(&schedule), action);
+        };
+
+        let trigger = CleanupTrigger::OnEvent {
+            event: type_name::<E>(),
+        };
+        register_stateless_cleanup::<C>(self, &PostUpdate, trigger, action);
+        self.add_event::<E>().add_systems(PostUpdate, cleanup)
+    }
+
+    fn add_conditional_cleanup<C: Cleanup, M>(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        condition: impl Condition<M>,
+    ) -> &mut Self {
+        let cleanup = cleanup::<C>(
+            None,
+            Some(format!("{:?}", schedule)),
+            CleanupAction::default(),
+        );
+
+        init_cleanup::<C>(self, CleanupAction::default());
+        let trigger = CleanupTrigger::OnCondition;
+        register_stateless_cleanup::<C>(self, &schedule, trigger, CleanupAction::default());
+        self.add_systems(schedule, cleanup.run_if(condition))
+    }
+
+    fn pause_cleanup_after<S: States>(&mut self, variant: S) -> &mut Self {
+        self.configure_set(Update, CleanupAfterSet.run_if(not(in_state(variant))))
+    }
+
+    fn propagate_cleanup_marker<C: Cleanup + Clone>(&mut self) -> &mut Self {
+        self.add_systems(PostUpdate, propagate::propagate_marker::<C>)
+    }
+
+    fn panic_on_cleanup_conflicts(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(CleanupRegistry::default)
+            .panic = true;
+        self
+    }
+
+    fn allow_multiple_cleanup_variants<C: Cleanup>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(CleanupRegistry::default)
+            .allow_multiple_variants
+            .insert(TypeId::of::<C>());
+        self
+    }
+
+    #[cfg(feature = "derive")]
+    fn add_cleanups<S: States>(&mut self) -> &mut Self {
+        for registration in inventory::iter::<CleanupRegistration> {
+            if (registration.state)() == TypeId::of::<S>() {
+                (registration.register)(self);
+            }
+        }
+        self
+    }
+}
+
+/// A state cleanup which is collected at compile time, and added to an app using
+/// [`AddStateCleanup::add_cleanups`].
+///
+/// You should not need to create these yourself - they are generated by the `Cleanup` derive
+/// macro for every `#[cleanup(state = ...)]` attribute on the type.
 #[cfg(feature = "derive")]
-pub use bevy_cleanup_derive::Cleanup;
+pub struct CleanupRegistration {
+    state: fn() -> TypeId,
+    register: fn(&mut App),
+}
+
+#[cfg(feature = "derive")]
+impl CleanupRegistration {
+    /// Creates a registration which calls `register` when the cleanups of the `S` state type are
+    /// added to an app.
+    pub const fn new<S: States>(register: fn(&mut App)) -> Self {
+        Self {
+            state: TypeId::of::<S>,
+            register,
+        }
+    }
+}
+
+#[cfg(feature = "derive")]
+inventory::collect!(CleanupRegistration);
+
+/// All [`Cleanup`] marker types which have had a cleanup registered for them.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct CleanupMarkers {
+    /// The type ID of each marker, mapped to the action of the first cleanup registered for it.
+    pub actions: HashMap<TypeId, CleanupAction>,
+    /// A function which cleans up all entities with the marker, for each marker in the order
+    /// that they were first registered.
+    pub cleanups: Vec<fn(&mut World)>,
+}
 
-/// The trait used to denote that a component is a "cleanup marker".
+/// Sets up the resources which are shared by all cleanups, and records `C` as a registered marker.
+pub(crate) fn init_cleanup<C: Cleanup>(app: &mut App, action: CleanupAction) {
+    let mut markers = app
+        .add_event::<CleanupReport>()
+        .world
+        .get_resource_or_insert_with(CleanupMarkers::default);
+    if markers.actions.contains_key(&TypeId::of::<C>()) {
+        return;
+    }
+
+    markers.actions.insert(TypeId::of::<C>(), action);
+    markers.cleanups.push(|world| {
+        world.cleanup::<C>();
+    });
+}
+
+/// Creates an exclusive system which recursively despawns all entities which have component `C`.
 ///
-/// This trait is never queried for by itself, but is a bound for
-/// [`AddStateCleanup::add_state_cleanup`], just to make sure you don't accidentally mark the wrong
-/// component as a cleanup component.
+/// This is the same system which is used by [`AddStateCleanup`] to clean up entities, so you can
+/// schedule it anywhere, with any run conditions, and it will behave exactly like a state cleanup.
 ///
-/// This marker trait is typically automatically derived using the `Cleanup` derive macro (it's
-/// simply an empty implementation block). You would typically keep your `States` enum and all
-/// `Cleanup`-deriving types close together.
+/// See [`AddStateCleanup::add_conditional_cleanup`] for a shorthand for adding this system with a
+/// run condition.
+///
+/// Unlike the methods of [`AddStateCleanup`], this doesn't add the [`CleanupReport`] event, so
+/// reports are only sent if the event was added to the app some other way.
 ///
 /// # Examples
 ///
 /// ```
 /// use bevy::prelude::*;
-/// use bevy_cleanup::Cleanup;
+/// use bevy_cleanup::{Cleanup, cleanup_system};
 ///
-/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
-/// enum AppState {
-///     #[default]
-///     Menu,
-///     Game,
-/// }
+/// #[derive(Resource)]
+/// struct Difficulty(f32);
 ///
 /// #[derive(Component, Cleanup)]
-/// struct CleanupMenu;
+/// struct CleanupEnemies;
 ///
-/// #[derive(Component, Cleanup)]
-/// struct CleanupGame;
+/// App::new()
+///     .insert_resource(Difficulty(1.0))
+///     .add_systems(
+///         Update,
+///         cleanup_system::<CleanupEnemies>().run_if(resource_changed::<Difficulty>()),
+///     );
 /// ```
-pub trait Cleanup: Component {}
+pub fn cleanup_system<C: Cleanup>() -> impl FnMut(&mut World) {
+    cleanup_system_with::<C>(CleanupAction::default())
+}
 
-/// Allows using [`Self::add_state_cleanup`].
-pub trait AddStateCleanup {
-    /// When the state `variant` is exited ([`OnExit`]), all entities which have component `C`
-    /// will be recursively despawned.
-    /// 
-    /// You do not have to add a state cleanup to *every* `S` variant, just the ones you want to
-    /// despawn entities for when you exit that variant. So if you know you won't be adding any
-    /// entities during a state (e.g. a `LoadGame` state), then don't bother adding state cleanup
-    /// to it.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use bevy::prelude::*;
-    /// use bevy_cleanup::{Cleanup, AddStateCleanup};
-    ///
-    /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
-    /// enum AppState {
-    ///     #[default]
-    ///     Menu,
-    ///     Game,
-    /// }
-    ///
-    /// #[derive(Component, Cleanup)]
-    /// struct CleanupMenu;
-    ///
-    /// #[derive(Component, Cleanup)]
-    /// struct CleanupGame;
-    ///
-    /// App::new()
-    ///     .add_state::<AppState>()
-    ///     .add_state_cleanup::<_, CleanupMenu>(AppState::Menu)
-    ///     .add_state_cleanup::<_, CleanupGame>(AppState::Game);
-    /// ```
-    fn add_state_cleanup<S: States, C: Cleanup>(&mut self, variant: S) -> &mut Self;
+/// Like [`cleanup_system`], but applies `action` to the entities instead of recursively despawning
+/// them.
+pub fn cleanup_system_with<C: Cleanup>(action: CleanupAction) -> impl FnMut(&mut World) {
+    cleanup::<C>(None, None, action)
 }
 
-impl AddStateCleanup for App {
-    fn add_state_cleanup<S: States, C: Cleanup>(&mut self, variant: S) -> &mut Self {
-        let cleanup = move |mut commands: Commands, query: Query<Entity, With<C>>| {
-            for entity in &query {
-                commands.entity(entity).despawn_recursive();
-            }
-        };
+fn state_cleanup<S: States, C: Cleanup>(
+    schedule: &impl Debug,
+    action: CleanupAction,
+) -> impl FnMut(&mut World) {
+    cleanup::<C>(
+        Some(type_name::<S>()),
+        Some(format!("{:?}", schedule)),
+        action,
+    )
+}
+
+fn cleanup<C: Cleanup>(
+    state: Option<&'static str>,
+    schedule: Option<String>,
+    action: CleanupAction,
+) -> impl FnMut(&mut World) {
+    move |world: &mut World| {
+        let entities = world
+            .query_filtered::<Entity, With<C>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        cleanup_entities::<C>(world, entities, state, schedule.as_deref(), action);
+    }
+}
+
+/// Applies `action` to all `entities` in a deterministic order, records the run in the
+/// [`CleanupStats`], then sends a [`CleanupReport`] if the event has been added.
+///
+/// In [`CleanupMode::DryRun`], this only logs the entities instead.
+pub(crate) fn cleanup_entities<C: Cleanup>(
+    world: &mut World,
+    mut entities: Vec<Entity>,
+    state: Option<&'static str>,
+    schedule: Option<&str>,
+    action: CleanupAction,
+) {
+    entities.sort();
+    if mode::dry_run(world, &entities, type_name::<C>(), state, schedule) {
+        return;
+    }
 
-        self.add_systems(OnExit(variant), cleanup)
+    let start = Instant::now();
+    for &entity in &entities {
+        action.apply::<C>(world, entity);
+    }
+    stats::record_stats(world, type_name::<C>(), entities.len(), start.elapsed());
+
+    if let Some(mut reports) = world.get_resource_mut::<Events<CleanupReport>>() {
+        reports.send(CleanupReport {
+            state,
+            schedule: schedule.map(str::to_owned),
+            marker: type_name::<C>(),
+            entities,
+        });
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use bevy::prelude::*;
+    use bevy::{ecs::event::ManualEventReader, prelude::*};
 
-    use super::{Cleanup, AddStateCleanup};
+    use super::{
+        cleanup_system, AddStateCleanup, Cleanup, CleanupAction, CleanupReport, CleanupResource,
+        CleanupSet, StateScoped,
+    };
     use crate as bevy_cleanup;
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
@@ -99,14 +1080,22 @@ mod tests {
         #[default]
         Menu,
         Game,
+        Pause,
     }
 
     #[derive(Component, Cleanup)]
+    #[cleanup(state = AppState::Menu)]
     struct CleanupMenu;
 
     #[derive(Component, Cleanup)]
+    #[cleanup(state = AppState::Game)]
     struct CleanupGame;
 
+    #[derive(Component, Cleanup)]
+    #[cleanup(state = AppState::Game)]
+    #[cleanup(state = AppState::Pause)]
+    struct CleanupPlaying;
+
     fn setup_menu(mut commands: Commands) {
         commands.spawn(CleanupMenu);
     }
@@ -144,6 +1133,266 @@ mod tests {
         assert_eq!(1, app.world.entities().len());
     }
 
+    #[test]
+    fn remove_on_enter() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .add_state_enter_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_systems(OnEnter(AppState::Game), setup_game);
+        app.update();
+
+        // a stale entity spawned late, outside of the game state
+        app.world.spawn(CleanupGame);
+        assert_eq!(1, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+    }
+
+    #[test]
+    fn remove_on_transition() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .add_transition_cleanup::<_, CleanupGame>(AppState::Game, AppState::Menu)
+            .add_systems(OnEnter(AppState::Game), setup_game);
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+
+        // Game -> Pause is not the registered transition
+        app.insert_resource(NextState(Some(AppState::Pause)));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(4, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+    }
+
+    #[test]
+    fn add_cleanups_from_attributes() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .add_cleanups::<AppState>()
+            .add_systems(OnEnter(AppState::Menu), setup_menu)
+            .add_systems(OnEnter(AppState::Game), setup_game);
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+    }
+
+    #[test]
+    fn add_cleanups_from_repeated_attributes() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .panic_on_cleanup_conflicts()
+            .add_cleanups::<AppState>();
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        app.world.spawn(CleanupPlaying);
+
+        app.insert_resource(NextState(Some(AppState::Pause)));
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+
+        app.world.spawn(CleanupPlaying);
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+    }
+
+    #[test]
+    fn remove_state_scoped() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .add_state_scoped_cleanup::<AppState>()
+            .add_systems(OnEnter(AppState::Menu), |mut commands: Commands| {
+                commands.spawn(StateScoped(AppState::Menu));
+            })
+            .add_systems(OnEnter(AppState::Game), |mut commands: Commands| {
+                commands.spawn(StateScoped(AppState::Game));
+                commands.spawn(StateScoped(AppState::Game));
+            });
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+    }
+
+    #[test]
+    fn cleanup_with_action() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .add_state_cleanup_with::<_, CleanupGame>(AppState::Game, CleanupAction::RemoveMarker)
+            .add_systems(OnEnter(AppState::Game), setup_game);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+        assert_eq!(0, app.world.query::<&CleanupGame>().iter(&app.world).count());
+    }
+
+    #[test]
+    fn report_on_cleanup() {
+        let mut app = app();
+        let mut reader = ManualEventReader::<CleanupReport>::default();
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        let reports = reader
+            .iter(app.world.resource::<Events<CleanupReport>>())
+            .collect::<Vec<_>>();
+        assert_eq!(1, reports.len());
+        assert_eq!(Some("OnExit(Menu)"), reports[0].schedule.as_deref());
+        assert_eq!(std::any::type_name::<CleanupMenu>(), reports[0].marker);
+        assert_eq!(1, reports[0].entities.len());
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        let reports = reader
+            .iter(app.world.resource::<Events<CleanupReport>>())
+            .collect::<Vec<_>>();
+        assert_eq!(1, reports.len());
+        assert_eq!(Some("OnExit(Game)"), reports[0].schedule.as_deref());
+        assert_eq!(2, reports[0].entities.len());
+    }
+
+    #[derive(Event)]
+    struct RoundEnded {
+        skip: bool,
+    }
+
+    #[test]
+    fn remove_on_event() {
+        let mut app = App::new();
+        app.add_event_cleanup_filtered::<RoundEnded, CleanupGame>(|event| !event.skip);
+        app.world.spawn(CleanupGame);
+
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+
+        app.world.send_event(RoundEnded { skip: true });
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+
+        app.world.send_event(RoundEnded { skip: true });
+        app.world.send_event(RoundEnded { skip: false });
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+    }
+
+    #[test]
+    fn event_cleanup_uses_registered_action() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup_with::<_, CleanupGame>(AppState::Game, CleanupAction::RemoveMarker)
+            .add_event_cleanup::<RoundEnded, CleanupGame>();
+        let entity = app.world.spawn(CleanupGame).id();
+
+        app.world.send_event(RoundEnded { skip: false });
+        app.update();
+        assert!(!app.world.entity(entity).contains::<CleanupGame>());
+    }
+
+    #[derive(Resource)]
+    struct Difficulty(usize);
+
+    #[test]
+    fn remove_on_condition() {
+        let mut app = App::new();
+        app.insert_resource(Difficulty(0))
+            .add_conditional_cleanup::<CleanupGame, _>(Update, resource_changed::<Difficulty>())
+            .add_systems(Update, cleanup_system::<CleanupMenu>().run_if(|| false));
+        app.update();
+
+        app.world.spawn(CleanupGame);
+        app.world.spawn(CleanupMenu);
+        app.update();
+        assert_eq!(2, app.world.entities().len());
+
+        app.world.resource_mut::<Difficulty>().0 += 1;
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+    }
+
+    #[derive(Resource, CleanupResource, Default)]
+    struct Level(usize);
+
+    #[test]
+    fn remove_resource_on_exit() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .add_state_resource_cleanup::<_, Level>(AppState::Game)
+            .add_systems(OnEnter(AppState::Game), |mut commands: Commands| {
+                commands.insert_resource(Level(1));
+            });
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert!(app.world.contains_resource::<Level>());
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert!(!app.world.contains_resource::<Level>());
+    }
+
+    #[test]
+    fn reset_resource_on_exit() {
+        let mut app = App::new();
+        app
+            .add_state::<AppState>()
+            .add_state_resource_reset::<_, Level>(AppState::Game)
+            .add_systems(OnEnter(AppState::Game), |mut commands: Commands| {
+                commands.insert_resource(Level(1));
+            });
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(1, app.world.resource::<Level>().0);
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(0, app.world.resource::<Level>().0);
+    }
+
     #[test]
     fn remove_on_reenter() {
         let mut app = app();
@@ -156,4 +1405,23 @@ mod tests {
         app.update();
         assert_eq!(1, app.world.entities().len());
     }
+
+    #[test]
+    fn setup_after_enter_cleanup() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_enter_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_systems(
+                OnEnter(AppState::Game),
+                (|world: &mut World| {
+                    world.spawn(CleanupGame);
+                })
+                .after(CleanupSet::<AppState>::new()),
+            );
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(1, app.world.entities().len());
+    }
 }
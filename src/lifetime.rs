@@ -0,0 +1,257 @@
+use std::{mem::discriminant, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{cleanup_entities, init_cleanup, Cleanup, CleanupAction};
+
+/// A [`Cleanup`] component which cleans up its entity once a timer elapses, rather than when a
+/// state is exited.
+///
+/// The timer is ticked by the [`CleanupAfterPlugin`], which must be added to the app. Once it
+/// finishes, the component's [`CleanupAction`] is applied to the entity, exactly as it would be
+/// for a state cleanup. Note that if the action is [`CleanupAction::RemoveMarker`], it is this
+/// component which gets removed.
+///
+/// Timers are ticked in the [`CleanupAfterSet`] system set, which can be paused while a state is
+/// active using [`AddStateCleanup::pause_cleanup_after`].
+///
+/// [`AddStateCleanup::pause_cleanup_after`]: crate::AddStateCleanup::pause_cleanup_after
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{CleanupAction, CleanupAfter, CleanupTime};
+///
+/// fn spawn_explosion(mut commands: Commands) {
+///     commands.spawn((
+///         Name::new("Explosion"),
+///         CleanupAfter::from_seconds(2.0),
+///     ));
+///
+///     commands.spawn((
+///         Name::new("Damage number"),
+///         CleanupAfter::from_seconds(1.0)
+///             .with_action(CleanupAction::Despawn)
+///             .with_time(CleanupTime::Real),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Component)]
+pub struct CleanupAfter {
+    /// The timer which cleans up the entity once it finishes.
+    pub timer: Timer,
+    /// The action applied to the entity once the timer finishes.
+    pub action: CleanupAction,
+    /// Which kind of time the timer is ticked with.
+    pub time: CleanupTime,
+}
+
+impl Cleanup for CleanupAfter {}
+
+impl CleanupAfter {
+    /// Creates a component which recursively despawns its entity after `duration` of virtual time.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+            action: CleanupAction::default(),
+            time: CleanupTime::default(),
+        }
+    }
+
+    /// Creates a component which recursively despawns its entity after `seconds` of virtual time.
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self::new(Duration::from_secs_f32(seconds))
+    }
+
+    /// Sets the action applied to the entity once the timer finishes.
+    pub fn with_action(mut self, action: CleanupAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Sets which kind of time the timer is ticked with.
+    pub fn with_time(mut self, time: CleanupTime) -> Self {
+        self.time = time;
+        self
+    }
+}
+
+/// Which kind of time a [`CleanupAfter`] timer is ticked with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CleanupTime {
+    /// Uses [`Time::delta`], which is affected by pausing and changing the relative speed of
+    /// [`Time`].
+    #[default]
+    Virtual,
+    /// Uses [`Time::raw_delta`], which is not affected by pausing or the relative speed of
+    /// [`Time`].
+    Real,
+}
+
+/// The system set in which [`CleanupAfter`] timers are ticked, and their entities cleaned up.
+///
+/// This runs in the [`Update`] schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct CleanupAfterSet;
+
+/// Ticks [`CleanupAfter`] timers, and cleans up their entities once they finish.
+///
+/// This requires a [`Time`] resource, which is usually added by the `TimePlugin`.
+pub struct CleanupAfterPlugin;
+
+impl Plugin for CleanupAfterPlugin {
+    fn build(&self, app: &mut App) {
+        init_cleanup::<CleanupAfter>(app, CleanupAction::default());
+        app.add_systems(Update, tick_cleanup_after.in_set(CleanupAfterSet));
+    }
+}
+
+fn tick_cleanup_after(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut CleanupAfter)>,
+) {
+    // the finished entities, grouped by the action applied to them
+    let mut finished = Vec::<(CleanupAction, Vec<Entity>)>::new();
+    for (entity, mut after) in &mut query {
+        let delta = match after.time {
+            CleanupTime::Virtual => time.delta(),
+            CleanupTime::Real => time.raw_delta(),
+        };
+        if !after.timer.tick(delta).just_finished() {
+            continue;
+        }
+        match finished
+            .iter_mut()
+            .find(|(action, _)| same_action(action, &after.action))
+        {
+            Some((_, entities)) => entities.push(entity),
+            None => finished.push((after.action, vec![entity])),
+        }
+    }
+
+    if finished.is_empty() {
+        return;
+    }
+    commands.add(move |world: &mut World| {
+        for (action, entities) in finished {
+            cleanup_entities::<CleanupAfter>(world, entities, None, None, action);
+        }
+    });
+}
+
+/// Returns `true` if `a` and `b` do the same thing to an entity.
+fn same_action(a: &CleanupAction, b: &CleanupAction) -> bool {
+    match (a, b) {
+        (CleanupAction::RemoveBundle(a), CleanupAction::RemoveBundle(b)) => {
+            *a as usize == *b as usize
+        }
+        (a, b) => discriminant(a) == discriminant(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+    use super::{CleanupAfter, CleanupAfterPlugin, CleanupTime};
+    use crate::{AddStateCleanup, CleanupAction, CleanupReport, CleanupStats, CleanupStatsPlugin};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+        Pause,
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, CleanupAfterPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs(1)));
+        // the first update only initializes the time
+        app.update();
+        app
+    }
+
+    #[test]
+    fn cleanup_after_timer() {
+        let mut app = app();
+        let entity = app.world.spawn(CleanupAfter::from_seconds(1.5)).id();
+
+        app.update();
+        assert!(app.world.get_entity(entity).is_some());
+
+        app.update();
+        assert!(app.world.get_entity(entity).is_none());
+    }
+
+    #[test]
+    fn cleanup_after_action() {
+        let mut app = app();
+        let entity = app
+            .world
+            .spawn(CleanupAfter::from_seconds(0.5).with_action(CleanupAction::RemoveMarker))
+            .id();
+
+        app.update();
+        assert!(!app.world.entity(entity).contains::<CleanupAfter>());
+    }
+
+    #[test]
+    fn cleanup_after_time() {
+        let mut app = app();
+        let virtual_time = app.world.spawn(CleanupAfter::from_seconds(0.5)).id();
+        let real_time = app
+            .world
+            .spawn(CleanupAfter::from_seconds(0.5).with_time(CleanupTime::Real))
+            .id();
+
+        app.world.resource_mut::<Time>().pause();
+        app.update();
+        assert!(app.world.get_entity(virtual_time).is_some());
+        assert!(app.world.get_entity(real_time).is_none());
+    }
+
+    #[test]
+    fn pause_in_state() {
+        let mut app = app();
+        app.add_state::<AppState>()
+            .pause_cleanup_after(AppState::Pause)
+            .insert_resource(NextState(Some(AppState::Pause)));
+        let entity = app.world.spawn(CleanupAfter::from_seconds(0.5)).id();
+
+        app.update();
+        assert!(app.world.get_entity(entity).is_some());
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert!(app.world.get_entity(entity).is_none());
+    }
+
+    #[test]
+    fn stats_and_reports() {
+        let mut app = app();
+        app.add_plugins(CleanupStatsPlugin);
+        app.world.spawn(CleanupAfter::from_seconds(0.5));
+        app.world.spawn(CleanupAfter::from_seconds(0.5));
+        app.world
+            .spawn(CleanupAfter::from_seconds(0.5).with_action(CleanupAction::RemoveMarker));
+
+        app.update();
+        let stats = app.world.resource::<CleanupStats>();
+        let after = stats.get::<CleanupAfter>().unwrap();
+        assert_eq!(2, after.runs);
+        assert_eq!(3, after.entities);
+
+        let reports = app.world.resource::<Events<CleanupReport>>();
+        let mut entities = reports
+            .iter_current_update_events()
+            .map(|report| report.entities.len())
+            .collect::<Vec<_>>();
+        entities.sort();
+        assert_eq!(vec![1, 2], entities);
+    }
+}
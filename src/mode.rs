@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+
+/// Controls what cleanups do when they run.
+///
+/// When this resource doesn't exist, cleanups behave as if it were [`CleanupMode::Normal`].
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::CleanupMode;
+///
+/// let mut app = App::new();
+/// // only log what every cleanup would do
+/// app.insert_resource(CleanupMode::DryRun);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Resource)]
+pub enum CleanupMode {
+    /// Cleanups apply their [`CleanupAction`](crate::CleanupAction) to entities.
+    #[default]
+    Normal,
+    /// Cleanups don't touch any entities or resources, and instead log the entities and resources
+    /// which they would have cleaned up, along with the marker type and the state that the cleanup
+    /// was registered for.
+    ///
+    /// No [`CleanupReport`](crate::CleanupReport)s are sent, and no
+    /// [`CleanupStats`](crate::CleanupStats) are recorded.
+    DryRun,
+}
+
+/// If the app is in [`CleanupMode::DryRun`], logs the entities which would be cleaned up and
+/// returns `true`.
+pub(crate) fn dry_run(
+    world: &World,
+    entities: &[Entity],
+    marker: &'static str,
+    state: Option<&'static str>,
+    schedule: Option<&str>,
+) -> bool {
+    if world.get_resource::<CleanupMode>() != Some(&CleanupMode::DryRun) {
+        return false;
+    }
+
+    for &entity in entities {
+        let name = world
+            .get::<Name>(entity)
+            .map(|name| format!(" ({})", name))
+            .unwrap_or_default();
+        info!(
+            "Dry run: would clean up {:?}{} with {} (state: {}, schedule: {})",
+            entity,
+            name,
+            marker,
+            state.unwrap_or("none"),
+            schedule.unwrap_or("none"),
+        );
+    }
+    true
+}
+
+/// If the app is in [`CleanupMode::DryRun`], logs that `resource` would be cleaned up in the way
+/// described by `action` (e.g. "remove") and returns `true`.
+pub(crate) fn dry_run_resource(
+    world: &World,
+    action: &str,
+    resource: &'static str,
+    state: &'static str,
+    schedule: &str,
+) -> bool {
+    if world.get_resource::<CleanupMode>() != Some(&CleanupMode::DryRun) {
+        return false;
+    }
+
+    info!(
+        "Dry run: would {} resource {} (state: {}, schedule: {})",
+        action, resource, state, schedule,
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::CleanupMode;
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup, CleanupBudget, CleanupCommandsExt, CleanupResource};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+        Menu,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupLevel;
+
+    #[derive(Resource, CleanupResource, Default)]
+    struct Level(usize);
+
+    #[derive(Resource, CleanupResource, Default)]
+    struct Score(usize);
+
+    #[test]
+    fn dry_run() {
+        let mut app = App::new();
+        app.insert_resource(CleanupMode::DryRun)
+            .add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_cleanup_budgeted::<_, CleanupLevel>(
+                AppState::Game,
+                CleanupBudget::Entities(1),
+            );
+        app.world.spawn((Name::new("Player"), CleanupGame));
+        app.world.spawn(CleanupLevel);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        app.update();
+        app.world.cleanup::<CleanupGame>();
+        assert_eq!(2, app.world.entities().len());
+    }
+
+    #[test]
+    fn dry_run_resources() {
+        let mut app = App::new();
+        app.insert_resource(CleanupMode::DryRun)
+            .insert_resource(Level(1))
+            .insert_resource(Score(1))
+            .add_state::<AppState>()
+            .add_state_resource_cleanup::<_, Level>(AppState::Game)
+            .add_state_resource_reset::<_, Score>(AppState::Game);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(1, app.world.resource::<Level>().0);
+        assert_eq!(1, app.world.resource::<Score>().0);
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use crate::{Cleanup, KeepOnCleanup, Persistent};
+
+/// Entities which the marker is not propagated to.
+type Kept = Or<(With<KeepOnCleanup>, With<Persistent>)>;
+
+/// Copies the cleanup marker `C` down the hierarchy, so that every descendant of an entity with
+/// `C` gets its own copy of `C`.
+///
+/// This runs for entities whose [`Parent`] changed, and for entities which were given `C`. Markers
+/// are never removed by this system, so an entity which was spawned under an entity with `C`
+/// keeps it even after being reparented.
+///
+/// Entities which are [`KeepOnCleanup`] or [`Persistent`], and their descendants, don't get the
+/// marker, since they are meant to outlive the cleanup of their ancestors.
+pub(crate) fn propagate_marker<C: Cleanup + Clone>(
+    mut commands: Commands,
+    reparented: Query<Entity, (Changed<Parent>, Without<C>)>,
+    marked: Query<Entity, Added<C>>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    markers: Query<&C>,
+    kept: Query<(), Kept>,
+) {
+    let inherited = reparented.iter().filter_map(|entity| {
+        parents
+            .iter_ancestors(entity)
+            .take_while(|&ancestor| !kept.contains(ancestor))
+            .find_map(|ancestor| markers.get(ancestor).ok())
+            .map(|marker| (entity, marker))
+    });
+    let added = marked
+        .iter()
+        .filter_map(|entity| markers.get(entity).ok().map(|marker| (entity, marker)));
+
+    for (root, marker) in inherited.chain(added) {
+        let mut stack = vec![root];
+        while let Some(entity) = stack.pop() {
+            if kept.contains(entity) {
+                continue;
+            }
+            if !markers.contains(entity) {
+                commands.entity(entity).insert(marker.clone());
+            }
+            stack.extend(children.get(entity).into_iter().flatten());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup, KeepOnCleanup, Persistent};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+        Menu,
+    }
+
+    #[derive(Clone, Component, Cleanup)]
+    struct CleanupGame;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .propagate_cleanup_marker::<CleanupGame>();
+        app
+    }
+
+    #[test]
+    fn propagate_to_children() {
+        let mut app = app();
+        let parent = app.world.spawn(CleanupGame).id();
+        let child = app.world.spawn_empty().set_parent(parent).id();
+        let grandchild = app.world.spawn_empty().set_parent(child).id();
+
+        app.update();
+        assert!(app.world.entity(child).contains::<CleanupGame>());
+        assert!(app.world.entity(grandchild).contains::<CleanupGame>());
+    }
+
+    #[test]
+    fn propagate_to_existing_children() {
+        let mut app = app();
+        let parent = app.world.spawn_empty().id();
+        let child = app.world.spawn_empty().set_parent(parent).id();
+        app.update();
+        assert!(!app.world.entity(child).contains::<CleanupGame>());
+
+        app.world.entity_mut(parent).insert(CleanupGame);
+        app.update();
+        assert!(app.world.entity(child).contains::<CleanupGame>());
+    }
+
+    #[test]
+    fn cleanup_after_reparent() {
+        let mut app = app();
+        let parent = app.world.spawn(CleanupGame).id();
+        let root = app.world.spawn(Name::new("Persistent root")).id();
+        let child = app.world.spawn_empty().set_parent(parent).id();
+        app.update();
+
+        app.world.entity_mut(child).set_parent(root);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert!(app.world.get_entity(root).is_some());
+        assert!(app.world.get_entity(child).is_none());
+    }
+
+    #[test]
+    fn keep_on_cleanup_not_propagated() {
+        let mut app = app();
+        let parent = app.world.spawn(CleanupGame).id();
+        let camera = app.world.spawn(KeepOnCleanup).set_parent(parent).id();
+        let camera_child = app.world.spawn_empty().set_parent(camera).id();
+        let music = app.world.spawn(Persistent).set_parent(parent).id();
+        app.update();
+        assert!(!app.world.entity(camera).contains::<CleanupGame>());
+        assert!(!app.world.entity(camera_child).contains::<CleanupGame>());
+        assert!(!app.world.entity(music).contains::<CleanupGame>());
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert!(app.world.get_entity(parent).is_none());
+        assert!(app.world.get_entity(camera).is_some());
+        assert!(app.world.get_entity(camera_child).is_some());
+    }
+}
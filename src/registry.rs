@@ -0,0 +1,449 @@
+use std::{
+    any::{type_name, TypeId},
+    fmt::Debug,
+};
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{Cleanup, CleanupAction, CleanupResource};
+
+/// Every cleanup which has been registered through [`AddStateCleanup`], including the cleanups of
+/// state resources, and cleanups which aren't tied to a state, such as those added using
+/// [`AddStateCleanup::add_event_cleanup`] or [`AddStateCleanup::add_conditional_cleanup`].
+///
+/// This can be used to answer questions like "what gets cleaned up when leaving
+/// `AppState::Game`?", e.g. to build tooling, or to assert in tests that a marker is cleaned up.
+///
+/// When a state cleanup is registered, it is also checked against the existing registrations, and a
+/// warning is logged (or a panic is raised, see [`AddStateCleanup::panic_on_cleanup_conflicts`])
+/// if:
+/// - the exact same cleanup - same state, variant, marker and schedule - was already registered.
+///   The duplicate cleanup is not added, since it would do nothing.
+/// - the marker is already cleaned up when exiting a different variant of the same state, unless
+///   this was allowed using [`AddStateCleanup::allow_multiple_cleanup_variants`].
+///
+/// [`AddStateCleanup`]: crate::AddStateCleanup
+/// [`AddStateCleanup::add_event_cleanup`]: crate::AddStateCleanup::add_event_cleanup
+/// [`AddStateCleanup::add_conditional_cleanup`]: crate::AddStateCleanup::add_conditional_cleanup
+/// [`AddStateCleanup::panic_on_cleanup_conflicts`]:
+///     crate::AddStateCleanup::panic_on_cleanup_conflicts
+/// [`AddStateCleanup::allow_multiple_cleanup_variants`]:
+///     crate::AddStateCleanup::allow_multiple_cleanup_variants
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, AddStateCleanup, CleanupRegistry};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     Game,
+/// }
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// let mut app = App::new();
+/// app.add_state::<AppState>()
+///     .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+///
+/// let registry = app.world.resource::<CleanupRegistry>();
+/// let markers = registry
+///     .for_variant(&AppState::Game)
+///     .map(|cleanup| cleanup.marker)
+///     .collect::<Vec<_>>();
+/// assert!(markers[0].ends_with("CleanupGame"));
+/// ```
+#[derive(Debug, Default, Resource)]
+pub struct CleanupRegistry {
+    pub(crate) panic: bool,
+    pub(crate) allow_multiple_variants: HashSet<TypeId>,
+    /// The type name and the debug representations of all variants of each state type which has
+    /// had a cleanup registered for it.
+    pub(crate) states: Vec<(TypeId, &'static str, Vec<String>)>,
+    entries: Vec<RegisteredCleanup>,
+}
+
+impl CleanupRegistry {
+    /// Returns the number of registered cleanups.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no cleanups have been registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over all registered cleanups, in the order that they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = &RegisteredCleanup> {
+        self.entries.iter()
+    }
+
+    /// Iterates over the cleanups registered for any variant of the state `S`.
+    pub fn for_state<S: States>(&self) -> impl Iterator<Item = &RegisteredCleanup> {
+        self.iter()
+            .filter(|cleanup| cleanup.state_id == Some(TypeId::of::<S>()))
+    }
+
+    /// Iterates over the cleanups registered for the state `variant`, in any schedule.
+    pub fn for_variant<S: States>(&self, variant: &S) -> impl Iterator<Item = &RegisteredCleanup> {
+        let variant = format!("{:?}", variant);
+        self.for_state::<S>()
+            .filter(move |cleanup| cleanup.variant.as_ref() == Some(&variant))
+    }
+
+    /// Iterates over the cleanups registered for the marker `C`, for any state or none.
+    pub fn for_marker<C: Cleanup>(&self) -> impl Iterator<Item = &RegisteredCleanup> {
+        self.iter()
+            .filter(|cleanup| cleanup.marker_id == TypeId::of::<C>())
+    }
+}
+
+/// A cleanup which was registered through [`AddStateCleanup`](crate::AddStateCleanup).
+#[derive(Debug, Clone)]
+pub struct RegisteredCleanup {
+    /// The type name of the `States` type that the cleanup was registered for, or `None` if it
+    /// isn't tied to a state, e.g. for event cleanups.
+    pub state: Option<&'static str>,
+    /// The debug representation of the variant that the cleanup was registered for, or `None` if
+    /// it isn't tied to a state. For transition cleanups, this is the variant which is exited.
+    pub variant: Option<String>,
+    /// The type name of the [`Cleanup`] marker component, or of the resource for resource
+    /// cleanups.
+    pub marker: &'static str,
+    /// What is cleaned up.
+    pub kind: CleanupKind,
+    /// The action applied to the entities which are cleaned up. This is unused for resource
+    /// cleanups.
+    pub action: CleanupAction,
+    /// The debug representation of the schedule that the cleanup runs in, e.g. `OnExit(Game)`.
+    pub schedule: String,
+    /// When the cleanup runs, relative to [`Self::variant`].
+    pub trigger: CleanupTrigger,
+    state_id: Option<TypeId>,
+    pub(crate) marker_id: TypeId,
+}
+
+/// When a [`RegisteredCleanup`] runs, relative to the variant it was registered for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CleanupTrigger {
+    /// When the variant is exited.
+    OnExit,
+    /// When the variant is entered.
+    OnEnter,
+    /// When the variant is exited, and the variant with this debug representation is entered.
+    OnTransition {
+        /// The debug representation of the variant which is entered.
+        to: String,
+    },
+    /// When an event of this type is sent, regardless of the state. See
+    /// [`AddStateCleanup::add_event_cleanup`](crate::AddStateCleanup::add_event_cleanup).
+    OnEvent {
+        /// The type name of the event.
+        event: &'static str,
+    },
+    /// When the schedule runs and the run condition of the cleanup is met, regardless of the
+    /// state. See [`AddStateCleanup::add_conditional_cleanup`].
+    ///
+    /// [`AddStateCleanup::add_conditional_cleanup`]:
+    ///     crate::AddStateCleanup::add_conditional_cleanup
+    OnCondition,
+}
+
+/// What a [`RegisteredCleanup`] cleans up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CleanupKind {
+    /// The entities with the marker component.
+    Entities,
+    /// The resource, which is removed.
+    RemoveResource,
+    /// The resource, which is reset to its default value.
+    ResetResource,
+}
+
+/// Records a cleanup of `C` for `variant` in `schedule`, returning `false` if the exact same
+/// cleanup was already registered.
+pub(crate) fn register_cleanup<S: States, C: Cleanup>(
+    app: &mut App,
+    variant: &S,
+    schedule: &impl Debug,
+    trigger: CleanupTrigger,
+    action: CleanupAction,
+) -> bool {
+    let entry = RegisteredCleanup {
+        state: Some(type_name::<S>()),
+        variant: Some(format!("{:?}", variant)),
+        marker: type_name::<C>(),
+        kind: CleanupKind::Entities,
+        action,
+        schedule: format!("{:?}", schedule),
+        trigger,
+        state_id: Some(TypeId::of::<S>()),
+        marker_id: TypeId::of::<C>(),
+    };
+    register::<S>(app, entry)
+}
+
+/// Records a cleanup of the resource `R` for `variant` in `schedule`, returning `false` if the
+/// exact same cleanup was already registered.
+pub(crate) fn register_resource_cleanup<S: States, R: CleanupResource>(
+    app: &mut App,
+    variant: &S,
+    schedule: &impl Debug,
+    kind: CleanupKind,
+) -> bool {
+    let entry = RegisteredCleanup {
+        state: Some(type_name::<S>()),
+        variant: Some(format!("{:?}", variant)),
+        marker: type_name::<R>(),
+        kind,
+        action: CleanupAction::default(),
+        schedule: format!("{:?}", schedule),
+        trigger: CleanupTrigger::OnExit,
+        state_id: Some(TypeId::of::<S>()),
+        marker_id: TypeId::of::<R>(),
+    };
+    register::<S>(app, entry)
+}
+
+/// Records a cleanup of `C` in `schedule` which isn't tied to a state, like an event cleanup.
+///
+/// Unlike state cleanups, these are never checked for conflicts, since e.g. two event cleanups of
+/// the same marker and event may have different filters.
+pub(crate) fn register_stateless_cleanup<C: Cleanup>(
+    app: &mut App,
+    schedule: &impl Debug,
+    trigger: CleanupTrigger,
+    action: CleanupAction,
+) {
+    let entry = RegisteredCleanup {
+        state: None,
+        variant: None,
+        marker: type_name::<C>(),
+        kind: CleanupKind::Entities,
+        action,
+        schedule: format!("{:?}", schedule),
+        trigger,
+        state_id: None,
+        marker_id: TypeId::of::<C>(),
+    };
+    app.world
+        .get_resource_or_insert_with(CleanupRegistry::default)
+        .entries
+        .push(entry);
+}
+
+fn register<S: States>(app: &mut App, entry: RegisteredCleanup) -> bool {
+    let mut registry = app
+        .world
+        .get_resource_or_insert_with(CleanupRegistry::default);
+    if !registry.states.iter().any(|(id, ..)| *id == TypeId::of::<S>()) {
+        let variants = S::variants().map(|variant| format!("{:?}", variant));
+        registry
+            .states
+            .push((TypeId::of::<S>(), type_name::<S>(), variants.collect()));
+    }
+    let state = type_name::<S>();
+    let variant = |cleanup: &RegisteredCleanup| cleanup.variant.clone().unwrap_or_default();
+    // resources are expected to be cleaned up when exiting multiple variants
+    let exit = entry.trigger == CleanupTrigger::OnExit && entry.kind == CleanupKind::Entities;
+
+    let same_marker = || {
+        registry
+            .entries
+            .iter()
+            .filter(|other| other.state_id == entry.state_id && other.marker_id == entry.marker_id)
+    };
+    if same_marker().any(|other| {
+        other.variant == entry.variant
+            && other.schedule == entry.schedule
+            && other.kind == entry.kind
+    }) {
+        conflict(
+            registry.panic,
+            format!(
+                "Cleanup of {} in {} of {} is registered more than once",
+                entry.marker, entry.schedule, state,
+            ),
+        );
+        return false;
+    }
+
+    if exit && !registry.allow_multiple_variants.contains(&entry.marker_id) {
+        if let Some(other) = same_marker()
+            .find(|other| {
+                other.trigger == CleanupTrigger::OnExit
+                    && other.kind == CleanupKind::Entities
+                    && other.variant != entry.variant
+            })
+        {
+            conflict(
+                registry.panic,
+                format!(
+                    "{} is cleaned up when exiting both {} and {} of {} - if this is intended, \
+                     call `allow_multiple_cleanup_variants` for it before registering",
+                    entry.marker,
+                    variant(other),
+                    variant(&entry),
+                    state,
+                ),
+            );
+        }
+    }
+
+    registry.entries.push(entry);
+    true
+}
+
+fn conflict(panic: bool, message: String) {
+    if panic {
+        panic!("{}", message);
+    } else {
+        warn!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{CleanupKind, CleanupRegistry, CleanupTrigger};
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup, CleanupAction, CleanupResource};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Menu,
+        Game,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupMenu;
+
+    #[derive(Resource, CleanupResource, Default)]
+    struct Level;
+
+    #[test]
+    fn introspect() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupMenu>(AppState::Menu)
+            .add_state_cleanup_with::<_, CleanupGame>(AppState::Game, CleanupAction::Despawn)
+            .add_transition_cleanup::<_, CleanupMenu>(AppState::Game, AppState::Menu);
+        let registry = app.world.resource::<CleanupRegistry>();
+        assert_eq!(3, registry.for_state::<AppState>().count());
+
+        let game = registry.for_variant(&AppState::Game).collect::<Vec<_>>();
+        assert_eq!(2, game.len());
+        assert!(game[0].marker.ends_with("CleanupGame"));
+        assert!(matches!(game[0].action, CleanupAction::Despawn));
+        assert_eq!("OnExit(Game)", game[0].schedule);
+        assert_eq!(
+            CleanupTrigger::OnTransition {
+                to: "Menu".to_owned()
+            },
+            game[1].trigger
+        );
+
+        let menu = registry.for_marker::<CleanupMenu>().collect::<Vec<_>>();
+        assert_eq!(2, menu.len());
+        assert_eq!(Some("Menu"), menu[0].variant.as_deref());
+        assert_eq!(CleanupTrigger::OnExit, menu[0].trigger);
+    }
+
+    #[test]
+    fn duplicate_not_added() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_enter_cleanup::<_, CleanupGame>(AppState::Game);
+        assert_eq!(2, app.world.resource::<CleanupRegistry>().len());
+    }
+
+    #[test]
+    #[should_panic(expected = "registered more than once")]
+    fn duplicate_panics() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .panic_on_cleanup_conflicts()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+    }
+
+    #[test]
+    #[should_panic(expected = "cleaned up when exiting both Menu and Game")]
+    fn multiple_variants_panics() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .panic_on_cleanup_conflicts()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Menu)
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+    }
+
+    #[test]
+    fn multiple_variants_allowed() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .panic_on_cleanup_conflicts()
+            .allow_multiple_cleanup_variants::<CleanupGame>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Menu)
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+        assert_eq!(2, app.world.resource::<CleanupRegistry>().len());
+    }
+
+    #[test]
+    fn resources() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .panic_on_cleanup_conflicts()
+            .add_state_resource_cleanup::<_, Level>(AppState::Game)
+            .add_state_resource_reset::<_, Level>(AppState::Game)
+            .add_state_resource_cleanup::<_, Level>(AppState::Menu);
+        let registry = app.world.resource::<CleanupRegistry>();
+        let game = registry.for_variant(&AppState::Game).collect::<Vec<_>>();
+        assert_eq!(2, game.len());
+        assert!(game[0].marker.ends_with("Level"));
+        assert_eq!(CleanupKind::RemoveResource, game[0].kind);
+        assert_eq!(CleanupKind::ResetResource, game[1].kind);
+        assert_eq!("OnExit(Game)", game[1].schedule);
+    }
+
+    #[derive(Event)]
+    struct Restart;
+
+    #[test]
+    fn stateless() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_event_cleanup::<Restart, CleanupGame>()
+            .add_conditional_cleanup::<CleanupGame, _>(Update, || true);
+        let registry = app.world.resource::<CleanupRegistry>();
+        assert_eq!(3, registry.len());
+        assert_eq!(1, registry.for_state::<AppState>().count());
+
+        let game = registry.for_marker::<CleanupGame>().collect::<Vec<_>>();
+        assert_eq!(None, game[1].state);
+        assert_eq!(None, game[1].variant);
+        assert_eq!("PostUpdate", game[1].schedule);
+        assert_eq!(
+            CleanupTrigger::OnEvent {
+                event: std::any::type_name::<Restart>()
+            },
+            game[1].trigger
+        );
+        assert_eq!("Update", game[2].schedule);
+        assert_eq!(CleanupTrigger::OnCondition, game[2].trigger);
+    }
+}
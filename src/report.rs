@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+
+/// An event sent after every run of a cleanup registered through
+/// [`AddStateCleanup`](crate::AddStateCleanup) or added using
+/// [`cleanup_system`](crate::cleanup_system), describing what was cleaned up.
+///
+/// This is sent even if no entities were cleaned up, so that every transition which has a
+/// cleanup registered for it can be observed.
+///
+/// The event is added by every method of [`AddStateCleanup`](crate::AddStateCleanup) which
+/// registers a cleanup. If you only use [`cleanup_system`](crate::cleanup_system) or
+/// [`CleanupCommandsExt`](crate::CleanupCommandsExt), you must add it yourself using
+/// `app.add_event::<CleanupReport>()`, otherwise no reports are sent.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::CleanupReport;
+///
+/// fn log_cleanups(mut reports: EventReader<CleanupReport>) {
+///     for report in reports.iter() {
+///         info!(
+///             "Cleaned up {} entities with {}",
+///             report.entities.len(),
+///             report.marker,
+///         );
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Event)]
+pub struct CleanupReport {
+    /// The type name of the `States` type that the cleanup was registered for, if it was
+    /// registered for a state.
+    pub state: Option<&'static str>,
+    /// The debug representation of the schedule that the cleanup ran in, e.g. `OnExit(Game)`, if it
+    /// is known.
+    pub schedule: Option<String>,
+    /// The type name of the [`Cleanup`](crate::Cleanup) marker component.
+    pub marker: &'static str,
+    /// The entities which were cleaned up, in the order that they were cleaned up.
+    ///
+    /// This only includes the entities which had the marker component, not any of their
+    /// descendants which were despawned along with them.
+    pub entities: Vec<Entity>,
+}
@@ -0,0 +1,218 @@
+use std::{
+    any::type_name,
+    fmt::{self, Debug},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*, utils::get_short_name};
+
+/// The system set which contains all cleanup systems registered for the state `S` through
+/// [`AddStateCleanup`](crate::AddStateCleanup).
+///
+/// In every schedule which a cleanup is added to (e.g. [`OnExit`]), this set is split into three
+/// sub-sets which run one after another:
+/// - [`Self::before`] - empty, for systems which need to see the entities before they are cleaned
+///   up, such as saving the game
+/// - [`Self::despawn`] - the cleanup systems themselves
+/// - [`Self::after`] - empty, for systems which need to run after the entities are cleaned up
+///
+/// [`Self::new`] is the set containing all three of these.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, AddStateCleanup, CleanupSet};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     Game,
+/// }
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// #[derive(Component)]
+/// struct Player;
+///
+/// fn save_game(players: Query<&Transform, With<Player>>) {
+///     // the player still exists here
+/// }
+///
+/// App::new()
+///     .add_state::<AppState>()
+///     .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+///     .add_systems(
+///         OnExit(AppState::Game),
+///         save_game.in_set(CleanupSet::<AppState>::before()),
+///     );
+/// ```
+pub struct CleanupSet<S: States> {
+    phase: Option<Phase>,
+    _state: PhantomData<S>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Phase {
+    Before,
+    Despawn,
+    After,
+}
+
+impl<S: States> CleanupSet<S> {
+    /// The set containing all of [`Self::before`], [`Self::despawn`] and [`Self::after`].
+    pub fn new() -> Self {
+        Self::with_phase(None)
+    }
+
+    /// The sub-set which runs before any entities are cleaned up.
+    pub fn before() -> Self {
+        Self::with_phase(Some(Phase::Before))
+    }
+
+    /// The sub-set which the cleanup systems are in.
+    pub fn despawn() -> Self {
+        Self::with_phase(Some(Phase::Despawn))
+    }
+
+    /// The sub-set which runs after all entities are cleaned up.
+    ///
+    /// Cleanups added using
+    /// [`add_state_cleanup_budgeted`](crate::AddStateCleanup::add_state_cleanup_budgeted) only
+    /// start when [`Self::despawn`] runs, so their entities still exist when this set runs. Use the
+    /// [`cleanup_in_progress`](crate::cleanup_in_progress) run condition to find out when they are
+    /// gone.
+    pub fn after() -> Self {
+        Self::with_phase(Some(Phase::After))
+    }
+
+    fn with_phase(phase: Option<Phase>) -> Self {
+        Self {
+            phase,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<S: States> Default for CleanupSet<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: States> Debug for CleanupSet<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CleanupSet<{}>", get_short_name(type_name::<S>()))?;
+        match self.phase {
+            Some(phase) => write!(f, "::{:?}", phase),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<S: States> Clone for CleanupSet<S> {
+    fn clone(&self) -> Self {
+        Self::with_phase(self.phase)
+    }
+}
+
+impl<S: States> PartialEq for CleanupSet<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.phase == other.phase
+    }
+}
+
+impl<S: States> Eq for CleanupSet<S> {}
+
+impl<S: States> Hash for CleanupSet<S> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.phase.hash(state);
+    }
+}
+
+impl<S: States> SystemSet for CleanupSet<S> {
+    fn dyn_clone(&self) -> Box<dyn SystemSet> {
+        Box::new(self.clone())
+    }
+}
+
+/// Adds `systems` to the [`CleanupSet::despawn`] set of `S` in `schedule`, and orders the sub-sets
+/// of the [`CleanupSet`] in that schedule.
+pub(crate) fn add_cleanup_systems<S: States, M>(
+    app: &mut App,
+    schedule: impl ScheduleLabel + Clone,
+    systems: impl IntoSystemConfigs<M>,
+) {
+    app.configure_sets(
+        schedule.clone(),
+        (
+            CleanupSet::<S>::before(),
+            CleanupSet::<S>::despawn(),
+            CleanupSet::<S>::after(),
+        )
+            .chain()
+            .in_set(CleanupSet::<S>::new()),
+    )
+    .add_systems(schedule, systems.in_set(CleanupSet::<S>::despawn()));
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::CleanupSet;
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+        Menu,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[derive(Component, Cleanup)]
+    struct CleanupLevel;
+
+    #[derive(Default, Resource)]
+    struct Seen {
+        before: usize,
+        after: usize,
+    }
+
+    #[test]
+    fn order_around_cleanup() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .init_resource::<Seen>()
+            .add_systems(
+                OnExit(AppState::Game),
+                (
+                    (|query: Query<(), With<CleanupGame>>, mut seen: ResMut<Seen>| {
+                        seen.before = query.iter().count();
+                    })
+                    .in_set(CleanupSet::<AppState>::before()),
+                    (|query: Query<(), With<CleanupGame>>, mut seen: ResMut<Seen>| {
+                        seen.after = query.iter().count();
+                    })
+                    .in_set(CleanupSet::<AppState>::after()),
+                ),
+            )
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_state_cleanup::<_, CleanupLevel>(AppState::Game);
+        app.world.spawn(CleanupGame);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        let seen = app.world.resource::<Seen>();
+        assert_eq!(1, seen.before);
+        assert_eq!(0, seen.after);
+    }
+}
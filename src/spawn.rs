@@ -0,0 +1,172 @@
+use bevy::{
+    ecs::{system::EntityCommands, world::EntityMut},
+    prelude::*,
+    utils::HashMap,
+};
+
+use crate::Cleanup;
+
+/// Allows spawning entities which automatically get the cleanup marker of the current state.
+///
+/// When a cleanup is added with [`AddStateCleanup::add_state_cleanup`] (or
+/// [`AddStateCleanup::add_state_cleanup_with`]), the marker is registered for that state variant
+/// if it can be created using [`Cleanup::marker`]. Entities spawned using
+/// [`Self::spawn_scoped`] then get the marker which was first registered for the current
+/// variant of `S`, so you don't have to remember to add it yourself.
+///
+/// [`AddStateCleanup::add_state_cleanup`]: crate::AddStateCleanup::add_state_cleanup
+/// [`AddStateCleanup::add_state_cleanup_with`]: crate::AddStateCleanup::add_state_cleanup_with
+///
+/// # Panics
+///
+/// When the spawn command is applied, this panics if no marker is registered for the current
+/// variant of `S`.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, AddStateCleanup, SpawnScopedExt};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     Game,
+/// }
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// fn setup_game(mut commands: Commands) {
+///     // gets `CleanupGame` automatically
+///     commands
+///         .spawn_scoped::<AppState>(Name::new("Level"))
+///         .with_children(|parent| {
+///             parent.spawn_scoped::<AppState>(Name::new("Player"));
+///         });
+/// }
+///
+/// App::new()
+///     .add_state::<AppState>()
+///     .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+///     .add_systems(OnEnter(AppState::Game), setup_game);
+/// ```
+pub trait SpawnScopedExt<'w, 's> {
+    /// Spawns an entity with `bundle`, and the cleanup marker registered for the current variant
+    /// of the state `S`.
+    fn spawn_scoped<S: States>(&mut self, bundle: impl Bundle) -> EntityCommands<'w, 's, '_>;
+}
+
+impl<'w, 's> SpawnScopedExt<'w, 's> for Commands<'w, 's> {
+    fn spawn_scoped<S: States>(&mut self, bundle: impl Bundle) -> EntityCommands<'w, 's, '_> {
+        let mut entity = self.spawn(bundle);
+        entity.add(insert_scoped_marker::<S>);
+        entity
+    }
+}
+
+impl<'w, 's> SpawnScopedExt<'w, 's> for ChildBuilder<'w, 's, '_> {
+    fn spawn_scoped<S: States>(&mut self, bundle: impl Bundle) -> EntityCommands<'w, 's, '_> {
+        let mut entity = self.spawn(bundle);
+        entity.add(insert_scoped_marker::<S>);
+        entity
+    }
+}
+
+/// The functions which insert the cleanup marker registered for each variant of `S`.
+#[derive(Resource)]
+pub(crate) struct ScopedMarkers<S: States>(HashMap<S, fn(&mut EntityMut)>);
+
+impl<S: States> Default for ScopedMarkers<S> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+/// Registers `C` as the marker for `variant`, if it can be created and no other marker has been
+/// registered for `variant` yet.
+pub(crate) fn register_scoped_marker<S: States, C: Cleanup>(app: &mut App, variant: S) {
+    if C::marker().is_none() {
+        return;
+    }
+
+    app.world
+        .get_resource_or_insert_with(ScopedMarkers::<S>::default)
+        .0
+        .entry(variant)
+        .or_insert(|entity| {
+            if let Some(marker) = C::marker() {
+                entity.insert(marker);
+            }
+        });
+}
+
+fn insert_scoped_marker<S: States>(entity: Entity, world: &mut World) {
+    let state = world.resource::<State<S>>().get();
+    let insert = world
+        .get_resource::<ScopedMarkers<S>>()
+        .and_then(|markers| markers.0.get(state).copied())
+        .unwrap_or_else(|| {
+            panic!(
+                "No cleanup marker is registered for {:?}, so {:?} cannot be spawned scoped to it",
+                state, entity,
+            )
+        });
+    insert(&mut world.entity_mut(entity));
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::SpawnScopedExt;
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Menu,
+        Game,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[test]
+    fn spawn_scoped() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_systems(OnEnter(AppState::Game), |mut commands: Commands| {
+                commands.spawn_scoped::<AppState>(()).with_children(|parent| {
+                    parent.spawn_scoped::<AppState>(());
+                });
+            });
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Game)));
+        app.update();
+        assert_eq!(
+            2,
+            app.world.query::<&CleanupGame>().iter(&app.world).count()
+        );
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        assert_eq!(0, app.world.entities().len());
+    }
+
+    #[test]
+    #[should_panic(expected = "No cleanup marker is registered for Menu")]
+    fn spawn_scoped_unregistered() {
+        let mut app = App::new();
+        app.add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game)
+            .add_systems(Update, |mut commands: Commands| {
+                commands.spawn_scoped::<AppState>(());
+            });
+        app.update();
+    }
+}
@@ -0,0 +1,252 @@
+use std::{any::type_name, time::Duration};
+
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticId, DiagnosticMeasurement, DiagnosticsStore,
+        MAX_DIAGNOSTIC_NAME_WIDTH,
+    },
+    prelude::*,
+    utils::{get_short_name, HashMap, HashSet, Instant},
+};
+
+use crate::Cleanup;
+
+/// Statistics about the cleanups of each [`Cleanup`] marker, which are kept up to date by the
+/// [`CleanupStatsPlugin`].
+///
+/// Every cleanup registered through [`AddStateCleanup`](crate::AddStateCleanup) or added using
+/// [`cleanup_system`](crate::cleanup_system), and every manual cleanup, counts as a run of the
+/// cleanup of its marker.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_cleanup::{Cleanup, CleanupStats};
+///
+/// #[derive(Component, Cleanup)]
+/// struct CleanupGame;
+///
+/// fn log_teardown_cost(stats: Res<CleanupStats>) {
+///     if let Some(stats) = stats.get::<CleanupGame>() {
+///         info!(
+///             "Cleaned up {} game entities in {:?}",
+///             stats.last_entities, stats.last_duration,
+///         );
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, Resource)]
+pub struct CleanupStats {
+    markers: HashMap<&'static str, CleanupMarkerStats>,
+}
+
+impl CleanupStats {
+    /// Gets the statistics of the marker `C`, if it has been cleaned up at least once.
+    pub fn get<C: Cleanup>(&self) -> Option<&CleanupMarkerStats> {
+        self.markers.get(type_name::<C>())
+    }
+
+    /// Iterates over the type name of each marker which has been cleaned up at least once, along
+    /// with its statistics.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &CleanupMarkerStats)> {
+        self.markers.iter().map(|(&marker, stats)| (marker, stats))
+    }
+}
+
+/// Statistics about the cleanups of a single [`Cleanup`] marker.
+#[derive(Debug, Clone, Default)]
+pub struct CleanupMarkerStats {
+    /// How many times the marker has been cleaned up.
+    pub runs: usize,
+    /// How many entities with the marker have been cleaned up in total.
+    pub entities: usize,
+    /// How many entities with the marker were cleaned up in the last run.
+    pub last_entities: usize,
+    /// How long the last run took.
+    pub last_duration: Duration,
+}
+
+/// Keeps the [`CleanupStats`] resource up to date, and adds a [`Diagnostic`] for each marker.
+///
+/// Each cleaned up marker gets two diagnostics in the [`DiagnosticsStore`], which are measured
+/// every time the marker is cleaned up:
+/// - `bevy_cleanup/<marker>` - how long the cleanup took, in milliseconds
+/// - `bevy_cleanup/<marker>/count` - how many entities were cleaned up
+///
+/// so they show up in the output of the `LogDiagnosticsPlugin`.
+///
+/// `<marker>` is the short type name of the marker, cut off so that the names fit in
+/// [`MAX_DIAGNOSTIC_NAME_WIDTH`]. If this name is already used by another marker, e.g. because two
+/// markers in different modules have the same name, `#2`, `#3` etc. is appended to it. Use
+/// [`CleanupStats`] to get the statistics of a marker by its full type name.
+pub struct CleanupStatsPlugin;
+
+impl Plugin for CleanupStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CleanupStats>()
+            .init_resource::<DiagnosticsStore>()
+            .add_systems(Last, measure_diagnostics);
+    }
+}
+
+/// Records a run of the cleanup of `marker`, if the [`CleanupStats`] resource exists.
+pub(crate) fn record_stats(
+    world: &mut World,
+    marker: &'static str,
+    entities: usize,
+    duration: Duration,
+) {
+    let Some(mut stats) = world.get_resource_mut::<CleanupStats>() else {
+        return;
+    };
+    let stats = stats.markers.entry(marker).or_default();
+    stats.runs += 1;
+    stats.entities += entities;
+    stats.last_entities = entities;
+    stats.last_duration = duration;
+}
+
+struct MarkerDiagnostics {
+    runs: usize,
+    duration: DiagnosticId,
+    entities: DiagnosticId,
+}
+
+/// The suffix of the name of the entity count diagnostic of a marker.
+const ENTITIES_SUFFIX: &str = "/count";
+
+fn measure_diagnostics(
+    stats: Res<CleanupStats>,
+    mut store: ResMut<DiagnosticsStore>,
+    mut measured: Local<HashMap<&'static str, MarkerDiagnostics>>,
+    mut names: Local<HashSet<String>>,
+) {
+    if !stats.is_changed() {
+        return;
+    }
+
+    // sorted, so that markers with the same short name are numbered consistently
+    let mut markers = stats.markers.iter().collect::<Vec<_>>();
+    markers.sort_by_key(|(&marker, _)| marker);
+    for (&marker, stats) in markers {
+        let diagnostics = measured.entry(marker).or_insert_with(|| {
+            let name = diagnostic_name(marker, &names);
+            names.insert(name.clone());
+            let duration = DiagnosticId::default();
+            let entities = DiagnosticId::default();
+            store.add(Diagnostic::new(duration, name.clone(), 20).with_suffix("ms"));
+            store.add(Diagnostic::new(entities, name + ENTITIES_SUFFIX, 20));
+            MarkerDiagnostics {
+                runs: 0,
+                duration,
+                entities,
+            }
+        });
+        if diagnostics.runs == stats.runs {
+            continue;
+        }
+        diagnostics.runs = stats.runs;
+
+        let now = Instant::now();
+        let measurements = [
+            (diagnostics.duration, stats.last_duration.as_secs_f64() * 1000.0),
+            (diagnostics.entities, stats.last_entities as f64),
+        ];
+        for (id, value) in measurements {
+            if let Some(diagnostic) = store.get_mut(id).filter(|diagnostic| diagnostic.is_enabled) {
+                diagnostic.add_measurement(DiagnosticMeasurement { time: now, value });
+            }
+        }
+    }
+}
+
+/// Creates a diagnostic name for `marker` which isn't in `used`, and which is short enough that
+/// [`ENTITIES_SUFFIX`] can be appended to it.
+fn diagnostic_name(marker: &str, used: &HashSet<String>) -> String {
+    let max_len = MAX_DIAGNOSTIC_NAME_WIDTH - ENTITIES_SUFFIX.len();
+    let base = format!("bevy_cleanup/{}", get_short_name(marker));
+    (1..)
+        .map(|index| {
+            let tag = match index {
+                1 => String::new(),
+                index => format!("#{}", index),
+            };
+            let mut name = base
+                .chars()
+                .take(max_len - tag.len())
+                .collect::<String>();
+            name.push_str(&tag);
+            name
+        })
+        .find(|name| !used.contains(name))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        diagnostic::{DiagnosticsStore, MAX_DIAGNOSTIC_NAME_WIDTH},
+        prelude::*,
+        utils::HashSet,
+    };
+
+    use super::{diagnostic_name, CleanupStats, CleanupStatsPlugin};
+    use crate as bevy_cleanup;
+    use crate::{AddStateCleanup, Cleanup};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, States)]
+    enum AppState {
+        #[default]
+        Game,
+        Menu,
+    }
+
+    #[derive(Component, Cleanup)]
+    struct CleanupGame;
+
+    #[test]
+    fn stats_and_diagnostics() {
+        let mut app = App::new();
+        app.add_plugins(CleanupStatsPlugin)
+            .add_state::<AppState>()
+            .add_state_cleanup::<_, CleanupGame>(AppState::Game);
+        app.world.spawn(CleanupGame);
+        app.world.spawn(CleanupGame);
+        app.update();
+
+        app.insert_resource(NextState(Some(AppState::Menu)));
+        app.update();
+        let stats = app.world.resource::<CleanupStats>();
+        let game = stats.get::<CleanupGame>().unwrap();
+        assert_eq!(1, game.runs);
+        assert_eq!(2, game.entities);
+        assert_eq!(2, game.last_entities);
+
+        let store = app.world.resource::<DiagnosticsStore>();
+        let entities = store
+            .iter()
+            .find(|diagnostic| diagnostic.name == "bevy_cleanup/CleanupGame/count")
+            .unwrap();
+        assert_eq!(Some(2.0), entities.value());
+        assert!(store
+            .iter()
+            .any(|diagnostic| diagnostic.name == "bevy_cleanup/CleanupGame"));
+    }
+
+    #[test]
+    fn diagnostic_names() {
+        let mut used = HashSet::new();
+        let game = diagnostic_name("a::CleanupGame", &used);
+        assert_eq!("bevy_cleanup/CleanupGame", game);
+        used.insert(game);
+        assert_eq!("bevy_cleanup/CleanupGame#2", diagnostic_name("b::CleanupGame", &used));
+
+        let long = diagnostic_name("a::CleanupEverythingInTheGameWorld", &used);
+        assert_eq!("bevy_cleanup/CleanupEveryt", long);
+        used.insert(long);
+        let long = diagnostic_name("b::CleanupEverythingInTheGameWorld", &used);
+        assert_eq!("bevy_cleanup/CleanupEver#2", long);
+        assert!(long.len() + "/count".len() <= MAX_DIAGNOSTIC_NAME_WIDTH);
+    }
+}